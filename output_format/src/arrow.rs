@@ -0,0 +1,127 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Apache Arrow IPC and Parquet output for lists of records.
+
+use std::any::type_name;
+use std::io;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use arrow_array::{
+    ArrayRef, BooleanArray, Float64Array, Int64Array, RecordBatch, RecordBatchOptions, StringArray,
+    UInt64Array,
+};
+use arrow_ipc::writer::FileWriter;
+use arrow_schema::{ArrowError, Field, Schema};
+use parquet::arrow::ArrowWriter;
+
+use crate::{ColumnType, OutputError, OutputFormat};
+
+/// Converts records into Arrow record batch.
+///
+/// Columns are built from [`OutputFormat::output_headers`] and
+/// [`OutputFormat::output_fields`], such that the batch holds exactly the
+/// data of the tabular output formats. The schema is defined by
+/// [`OutputFormat::output_column_types`] and does not depend on the records,
+/// so an empty list gets the same schema as a non-empty one. Cells of
+/// non-string columns are parsed according to the column type, with empty
+/// cells stored as nulls. All columns are nullable.
+///
+/// # Errors
+///
+/// Returns [`OutputError::Arrow`] if a cell can't be parsed as a value of the
+/// column type.
+pub fn to_record_batch<T: OutputFormat>(items: &[T]) -> Result<RecordBatch, OutputError> {
+    let rows = items.iter().map(T::output_fields).collect::<Vec<_>>();
+    let headers = T::output_headers();
+    let types = T::output_column_types();
+    debug_assert_eq!(headers.len(), types.len(), "column types must match the headers");
+
+    let mut fields = Vec::with_capacity(headers.len());
+    let mut columns = Vec::with_capacity(headers.len());
+    for (no, (header, ty)) in headers.into_iter().zip(types).enumerate() {
+        let cells = rows.iter().map(|row| row.get(no).map(String::as_str)).collect::<Vec<_>>();
+        let column = to_column(&header, ty, &cells)?;
+        fields.push(Field::new(header, column.data_type().clone(), true));
+        columns.push(column);
+    }
+
+    let schema = Arc::new(Schema::new(fields));
+    Ok(RecordBatch::try_new_with_options(
+        schema,
+        columns,
+        &RecordBatchOptions::new().with_row_count(Some(rows.len())),
+    )?)
+}
+
+/// Writes records into Arrow IPC file.
+pub fn write_arrow_ipc<T: OutputFormat>(
+    items: &[T],
+    f: &mut impl io::Write,
+) -> Result<(), OutputError> {
+    let batch = to_record_batch(items)?;
+    let mut writer = FileWriter::try_new(f, &batch.schema())?;
+    writer.write(&batch)?;
+    writer.finish()?;
+    Ok(())
+}
+
+/// Writes records into Parquet file.
+pub fn write_parquet<T: OutputFormat>(
+    items: &[T],
+    f: &mut (impl io::Write + Send),
+) -> Result<(), OutputError> {
+    let batch = to_record_batch(items)?;
+    let mut writer = ArrowWriter::try_new(f, batch.schema(), None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+/// Builds column of the given type from the cells of the tabular output.
+fn to_column(
+    header: &str,
+    ty: ColumnType,
+    cells: &[Option<&str>],
+) -> Result<ArrayRef, OutputError> {
+    Ok(match ty {
+        ColumnType::String => Arc::new(StringArray::from(cells.to_vec())),
+        ColumnType::Boolean => {
+            Arc::new(parse_cells::<bool>(header, cells)?.into_iter().collect::<BooleanArray>())
+        }
+        ColumnType::Int64 => Arc::new(Int64Array::from(parse_cells::<i64>(header, cells)?)),
+        ColumnType::UInt64 => Arc::new(UInt64Array::from(parse_cells::<u64>(header, cells)?)),
+        ColumnType::Float64 => Arc::new(Float64Array::from(parse_cells::<f64>(header, cells)?)),
+    })
+}
+
+/// Parses cells of a typed column; empty and missing cells become nulls.
+fn parse_cells<T: FromStr>(
+    header: &str,
+    cells: &[Option<&str>],
+) -> Result<Vec<Option<T>>, ArrowError> {
+    cells
+        .iter()
+        .map(|cell| match cell {
+            None | Some("") => Ok(None),
+            Some(cell) => cell.parse().map(Some).map_err(|_| {
+                ArrowError::ParseError(format!(
+                    "value `{}` of column `{}` is not {}",
+                    cell,
+                    header,
+                    type_name::<T>()
+                ))
+            }),
+        })
+        .collect()
+}
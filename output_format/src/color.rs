@@ -0,0 +1,149 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Policy of using ANSI colours in the output.
+
+use std::env;
+use std::ffi::OsString;
+use std::str::FromStr;
+
+/// Policy of using ANSI colours in the output
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Default, Display)]
+pub enum ColorChoice {
+    /// Use colours only if the output goes to a terminal. The decision can be
+    /// overridden with `CLICOLOR_FORCE`, `NO_COLOR` and `CLICOLOR` environment
+    /// variables
+    #[default]
+    #[display("auto")]
+    Auto,
+
+    /// Always use colours
+    #[display("always")]
+    Always,
+
+    /// Never use colours
+    #[display("never")]
+    Never,
+}
+
+impl ColorChoice {
+    /// Resolves [`ColorChoice::Auto`] policy into either
+    /// [`ColorChoice::Always`] or [`ColorChoice::Never`], depending on the
+    /// environment variables and whether the output goes to a terminal.
+    ///
+    /// Non-empty `CLICOLOR_FORCE` other than `0` enables colours; otherwise
+    /// non-empty `NO_COLOR` or `CLICOLOR=0` disable them.
+    pub fn resolve(self, is_terminal: bool) -> ColorChoice {
+        self.resolve_with(is_terminal, |name| env::var_os(name))
+    }
+
+    /// Resolves the policy like [`ColorChoice::resolve`] does, reading the
+    /// environment variables with the provided `env` function.
+    fn resolve_with(
+        self,
+        is_terminal: bool,
+        env: impl Fn(&str) -> Option<OsString>,
+    ) -> ColorChoice {
+        if self != ColorChoice::Auto {
+            return self;
+        }
+        let var = |name| env(name).filter(|val| !val.is_empty());
+        let enabled = if var("CLICOLOR_FORCE").is_some_and(|val| val != "0") {
+            true
+        } else if var("NO_COLOR").is_some() || var("CLICOLOR").is_some_and(|val| val == "0") {
+            false
+        } else {
+            is_terminal
+        };
+        if enabled {
+            ColorChoice::Always
+        } else {
+            ColorChoice::Never
+        }
+    }
+}
+
+impl FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim().to_lowercase().as_str() {
+            "auto" => ColorChoice::Auto,
+            "always" | "force" => ColorChoice::Always,
+            "never" | "none" => ColorChoice::Never,
+            _ => Err("Unknown colour policy")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn resolve(vars: &[(&str, &str)], is_terminal: bool) -> ColorChoice {
+        ColorChoice::Auto.resolve_with(is_terminal, |name| {
+            vars.iter().find(|(var, _)| *var == name).map(|(_, val)| OsString::from(val))
+        })
+    }
+
+    #[test]
+    fn terminal() {
+        assert_eq!(resolve(&[], true), ColorChoice::Always);
+        assert_eq!(resolve(&[], false), ColorChoice::Never);
+        assert_eq!(resolve(&[("CLICOLOR", "1")], false), ColorChoice::Never);
+    }
+
+    #[test]
+    fn explicit() {
+        let vars = [("CLICOLOR_FORCE", "1"), ("NO_COLOR", "1")];
+        let env = |name: &str| {
+            vars.iter().find(|(var, _)| *var == name).map(|(_, val)| OsString::from(val))
+        };
+        assert_eq!(ColorChoice::Always.resolve_with(false, env), ColorChoice::Always);
+        assert_eq!(ColorChoice::Never.resolve_with(true, env), ColorChoice::Never);
+    }
+
+    #[test]
+    fn clicolor_force() {
+        assert_eq!(resolve(&[("CLICOLOR_FORCE", "1")], false), ColorChoice::Always);
+        assert_eq!(
+            resolve(&[("CLICOLOR_FORCE", "1"), ("NO_COLOR", "1")], false),
+            ColorChoice::Always
+        );
+        assert_eq!(
+            resolve(&[("CLICOLOR_FORCE", "1"), ("CLICOLOR", "0")], false),
+            ColorChoice::Always
+        );
+        // Zero and empty values don't force the colours
+        assert_eq!(resolve(&[("CLICOLOR_FORCE", "0")], false), ColorChoice::Never);
+        assert_eq!(resolve(&[("CLICOLOR_FORCE", "")], false), ColorChoice::Never);
+        assert_eq!(
+            resolve(&[("CLICOLOR_FORCE", "0"), ("NO_COLOR", "1")], true),
+            ColorChoice::Never
+        );
+    }
+
+    #[test]
+    fn no_color() {
+        assert_eq!(resolve(&[("NO_COLOR", "1")], true), ColorChoice::Never);
+        assert_eq!(resolve(&[("NO_COLOR", "0")], true), ColorChoice::Never);
+        assert_eq!(resolve(&[("NO_COLOR", "1"), ("CLICOLOR", "1")], true), ColorChoice::Never);
+        // Empty value is ignored
+        assert_eq!(resolve(&[("NO_COLOR", "")], true), ColorChoice::Always);
+    }
+
+    #[test]
+    fn clicolor() {
+        assert_eq!(resolve(&[("CLICOLOR", "0")], true), ColorChoice::Never);
+        assert_eq!(resolve(&[("CLICOLOR", "")], true), ColorChoice::Always);
+    }
+}
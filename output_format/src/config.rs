@@ -0,0 +1,120 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+use crate::theme::Style;
+use crate::{ColorChoice, Theme, XmlKey};
+
+/// Options tuning the data output for specific formats
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct OutputConfig {
+    /// Policy of using colours. When printing, [`ColorChoice::Auto`] is
+    /// resolved against the standard output; when writing into an arbitrary
+    /// writer, it is assumed not to be a terminal
+    pub color: ColorChoice,
+
+    /// Colour theme used when the colours are enabled
+    pub theme: Theme,
+
+    /// Terminate CSV lines with CRLF (`\r\n`), as required by RFC 4180,
+    /// instead of a single LF (`\n`)
+    pub csv_crlf: bool,
+
+    /// Draw box borders around table cells in [`crate::Formatting::Table`]
+    /// output
+    pub table_borders: bool,
+
+    /// Wrap [`crate::Formatting::Html`] table into a standalone HTML document
+    /// with embedded minimal styling
+    pub html_standalone: bool,
+
+    /// CSS class assigned to the `<table>` element in
+    /// [`crate::Formatting::Html`] output
+    pub html_table_class: Option<String>,
+
+    /// Name of the field holding map key in each line of
+    /// [`crate::Formatting::JsonLines`] output for map collections. Records
+    /// having a field with the same name are nested under `value` field
+    pub jsonl_key_field: String,
+
+    /// Number of spaces used for each level of indentation in
+    /// [`crate::Formatting::JsonPretty`] output
+    pub json_indent: usize,
+
+    /// Name of the array of tables containing items of list-like collections
+    /// in [`crate::Formatting::Toml`] output, since TOML documents can't have
+    /// arrays at the top level
+    pub toml_list_key: String,
+
+    /// Name of the root element of [`crate::Formatting::Xml`] output for
+    /// collections
+    pub xml_root: String,
+
+    /// Name of the elements holding collection items in
+    /// [`crate::Formatting::Xml`] output; also used as the root element name
+    /// when a single record is output
+    pub xml_item: String,
+
+    /// Whether map keys are put into an attribute or a child element of the
+    /// item elements in [`crate::Formatting::Xml`] output
+    pub xml_key: XmlKey,
+
+    /// Name of the attribute or element holding map keys in
+    /// [`crate::Formatting::Xml`] output. With [`XmlKey::Element`], records
+    /// having a field with the same name are nested under `value` element
+    pub xml_key_name: String,
+
+    /// Name of the table created by [`crate::Formatting::Sql`] output
+    pub sql_table: String,
+
+    /// Name of the worksheet in [`crate::Formatting::Xlsx`] output, unless
+    /// the output contains a worksheet per each of the map keys
+    pub xlsx_sheet: String,
+
+    /// Print binary formats ([`crate::Formatting::Cbor`],
+    /// [`crate::Formatting::MsgPack`], [`crate::Formatting::Xlsx`]) even if the standard output is
+    /// a terminal
+    pub force_binary: bool,
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        OutputConfig {
+            color: ColorChoice::Auto,
+            theme: Theme::default(),
+            csv_crlf: false,
+            table_borders: false,
+            html_standalone: false,
+            html_table_class: None,
+            jsonl_key_field: s!("id"),
+            json_indent: 2,
+            toml_list_key: s!("items"),
+            xml_root: s!("items"),
+            xml_item: s!("item"),
+            xml_key: XmlKey::Attribute,
+            xml_key_name: s!("id"),
+            sql_table: s!("items"),
+            xlsx_sheet: s!("items"),
+            force_binary: false,
+        }
+    }
+}
+
+impl OutputConfig {
+    /// Detects whether the colours should be used according to the
+    /// [`OutputConfig::color`] policy.
+    pub fn colorize(&self) -> bool { self.color.resolve(false) == ColorChoice::Always }
+
+    /// Paints the text with the style if the colours are enabled.
+    pub(crate) fn paint(&self, text: &str, style: Style) -> String {
+        style.paint(text, self.colorize())
+    }
+}
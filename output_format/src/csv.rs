@@ -0,0 +1,82 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! CSV output according to RFC 4180.
+
+use std::borrow::Cow;
+use std::io;
+
+use crate::OutputConfig;
+
+/// Escapes a single CSV field: fields containing commas, double quotes or
+/// line breaks are wrapped into double quotes, with the quotes inside the
+/// field being doubled.
+pub fn csv_escape(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\r', '\n']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+/// Writes a single CSV record made of escaped `fields`, terminated with the
+/// line ending defined by the `config`.
+pub(crate) fn write_csv_record(
+    f: &mut impl io::Write,
+    fields: impl IntoIterator<Item = impl AsRef<str>>,
+    config: &OutputConfig,
+) -> io::Result<()> {
+    let line = fields
+        .into_iter()
+        .map(|field| csv_escape(field.as_ref()).into_owned())
+        .collect::<Vec<_>>()
+        .join(",");
+    f.write_all(line.as_bytes())?;
+    f.write_all(if config.csv_crlf { b"\r\n" } else { b"\n" })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn record(fields: &[&str], config: &OutputConfig) -> String {
+        let mut buf = Vec::new();
+        write_csv_record(&mut buf, fields, config).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn escape_plain() {
+        assert_eq!(csv_escape("plain value"), "plain value");
+        assert!(matches!(csv_escape("plain value"), Cow::Borrowed(_)));
+        assert_eq!(csv_escape(""), "");
+    }
+
+    #[test]
+    fn escape_special() {
+        assert_eq!(csv_escape("a,b"), "\"a,b\"");
+        assert_eq!(csv_escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_escape("\""), "\"\"\"\"");
+        assert_eq!(csv_escape("line\nbreak"), "\"line\nbreak\"");
+        assert_eq!(csv_escape("carriage\rreturn"), "\"carriage\rreturn\"");
+        assert_eq!(csv_escape("crlf\r\n"), "\"crlf\r\n\"");
+    }
+
+    #[test]
+    fn record_line_endings() {
+        let fields = ["a,b", "c\"d", "e"];
+        let mut config = OutputConfig::default();
+        assert_eq!(record(&fields, &config), "\"a,b\",\"c\"\"d\",e\n");
+        config.csv_crlf = true;
+        assert_eq!(record(&fields, &config), "\"a,b\",\"c\"\"d\",e\r\n");
+    }
+}
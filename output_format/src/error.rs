@@ -0,0 +1,95 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+use std::io;
+
+use crate::Formatting;
+
+/// Errors happening during data output
+#[derive(Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum OutputError {
+    /// I/O error during data output: {0}
+    Io(io::Error),
+
+    /// output was closed by the reading end of the pipe
+    BrokenPipe,
+
+    /// unable to serialize data as JSON: {0}
+    #[from]
+    Json(serde_json::Error),
+
+    /// unable to serialize data as YAML: {0}
+    #[from]
+    Yaml(serde_yaml::Error),
+
+    /// data can't be represented as TOML: {0}
+    #[from]
+    Toml(toml::ser::Error),
+
+    /// unable to serialize data as XML: {0}
+    Xml(serde_json::Error),
+
+    /// unable to serialize data as CBOR: {0}
+    #[from]
+    Cbor(ciborium::ser::Error<io::Error>),
+
+    /// unable to serialize data as MessagePack: {0}
+    #[from]
+    MsgPack(rmp_serde::encode::Error),
+
+    /// unable to serialize data as RON: {0}
+    #[from]
+    Ron(ron::Error),
+
+    /// unable to write Excel workbook: {0}
+    #[from]
+    Xlsx(rust_xlsxwriter::XlsxError),
+
+    /// unable to write Arrow data: {0}
+    #[cfg(feature = "arrow")]
+    #[from]
+    Arrow(arrow_schema::ArrowError),
+
+    /// unable to write Parquet data: {0}
+    #[cfg(feature = "arrow")]
+    #[from]
+    Parquet(parquet::errors::ParquetError),
+
+    /// unable to write SQLite database: {0}
+    #[cfg(feature = "sqlite")]
+    #[from]
+    Sqlite(rusqlite::Error),
+
+    /// map key field name `{0}` collides with the field holding the record
+    KeyCollision(String),
+
+    /// output format `{0}` is not supported for this kind of data
+    UnsupportedFormat(Formatting),
+
+    /// refusing to write binary {0} data to a terminal
+    BinaryToTerminal(Formatting),
+
+    /// no items to output
+    EmptyCollection,
+}
+
+impl From<io::Error> for OutputError {
+    /// Converts I/O error, distinguishing broken pipe (happening when the
+    /// output is piped into `head` or similar tools) from other failures.
+    fn from(err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::BrokenPipe => OutputError::BrokenPipe,
+            _ => OutputError::Io(err),
+        }
+    }
+}
@@ -0,0 +1,259 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Syntax highlighting of serialized JSON and YAML data.
+
+use crate::theme::Style;
+use crate::Theme;
+
+/// Highlights JSON document (either compact or pretty-printed) with the
+/// theme styles for object keys, strings, numbers and literals (`true`,
+/// `false` and `null`). Whitespace and punctuation are kept as is.
+pub fn highlight_json(json: &str, theme: &Theme) -> String {
+    let mut out = String::with_capacity(json.len() * 2);
+    let mut chars = json.char_indices().peekable();
+    while let Some((start, ch)) = chars.next() {
+        let style = match ch {
+            '"' => {
+                let mut escaped = false;
+                for (_, ch) in chars.by_ref() {
+                    match ch {
+                        _ if escaped => escaped = false,
+                        '\\' => escaped = true,
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+                let end = chars.peek().map(|(pos, _)| *pos).unwrap_or(json.len());
+                if json[end..].trim_start().starts_with(':') {
+                    theme.key
+                } else {
+                    theme.string
+                }
+            }
+            '-' | '0'..='9' => {
+                while chars
+                    .next_if(|(_, ch)| matches!(ch, '0'..='9' | '.' | 'e' | 'E' | '+' | '-'))
+                    .is_some()
+                {}
+                theme.number
+            }
+            'a'..='z' => {
+                while chars.next_if(|(_, ch)| ch.is_ascii_lowercase()).is_some() {}
+                theme.literal
+            }
+            ch => {
+                out.push(ch);
+                continue;
+            }
+        };
+        let end = chars.peek().map(|(pos, _)| *pos).unwrap_or(json.len());
+        out.push_str(&style.paint(&json[start..end], true));
+    }
+    out
+}
+
+/// Highlights YAML document produced by `serde_yaml` with the theme styles
+/// for mapping keys, strings, numbers and literals (`true`, `false`, `null`
+/// and `~`); document markers are painted with the accent style.
+///
+/// The highlighting is line-based and relies on the block style used by
+/// `serde_yaml`, where each line holds at most a single key and a scalar
+/// value, which are preceded by sequence item indicators.
+pub fn highlight_yaml(yaml: &str, theme: &Theme) -> String {
+    yaml.split('\n').map(|line| highlight_yaml_line(line, theme)).collect::<Vec<_>>().join("\n")
+}
+
+fn highlight_yaml_line(line: &str, theme: &Theme) -> String {
+    if line == "---" || line == "..." {
+        return theme.accent.paint(line, true);
+    }
+    let mut rest = line.trim_start();
+    let mut out = line[..line.len() - rest.len()].to_owned();
+    while let Some(item) = rest.strip_prefix('-').filter(|s| s.is_empty() || s.starts_with(' ')) {
+        let item_rest = item.trim_start();
+        out.push('-');
+        out.push_str(&item[..item.len() - item_rest.len()]);
+        rest = item_rest;
+    }
+    if let Some((key, value)) = split_yaml_key(rest) {
+        out.push_str(&theme.key.paint(key, true));
+        out.push(':');
+        let scalar = value.trim_start();
+        out.push_str(&value[..value.len() - scalar.len()]);
+        rest = scalar;
+    }
+    out.push_str(&yaml_scalar_style(rest, theme).paint(rest, true));
+    out
+}
+
+/// Splits `key: value` line into the key and the value following the colon.
+fn split_yaml_key(s: &str) -> Option<(&str, &str)> {
+    let key_end = match s.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let mut escaped = false;
+            let mut end = None;
+            for (pos, ch) in s.char_indices().skip(1) {
+                match ch {
+                    _ if escaped => escaped = false,
+                    '\\' if quote == '"' => escaped = true,
+                    ch if ch == quote => {
+                        end = Some(pos + 1);
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+            end?
+        }
+        // Plain scalars can't contain colon followed by a space
+        _ => s.find(": ").or_else(|| s.strip_suffix(':').map(str::len))?,
+    };
+    let value = s[key_end..].strip_prefix(':')?;
+    if !value.is_empty() && !value.starts_with(' ') {
+        return None;
+    }
+    Some((&s[..key_end], value))
+}
+
+fn yaml_scalar_style(scalar: &str, theme: &Theme) -> Style {
+    match scalar {
+        "" | "[]" | "{}" => Style::plain(),
+        "~" | "null" | "true" | "false" => theme.literal,
+        ".inf" | "-.inf" | ".nan" => theme.number,
+        _ if scalar.starts_with(['"', '\'']) => theme.string,
+        _ if scalar.starts_with(|ch: char| ch.is_ascii_digit() || ch == '-' || ch == '+')
+            && scalar.parse::<f64>().is_ok() =>
+        {
+            theme.number
+        }
+        _ => theme.string,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+    use crate::width::strip_ansi;
+    use crate::{write_serialized, ColorChoice, Formatting, OutputConfig};
+
+    fn theme() -> Theme { Theme::default() }
+
+    fn key(s: &str) -> String { theme().key.paint(s, true) }
+    fn string(s: &str) -> String { theme().string.paint(s, true) }
+    fn number(s: &str) -> String { theme().number.paint(s, true) }
+    fn literal(s: &str) -> String { theme().literal.paint(s, true) }
+
+    fn sample() -> serde_json::Value {
+        json!({
+            "txid": "ab\"c\": d",
+            "amounts": [-1.5e-7, 2e21, 0, -3],
+            "flags": { "spent": true, "label": null, "empty": [] },
+            "nested": [["a", 1], [{ "x: y": false }]],
+            "multi\nline": "it's \\ here"
+        })
+    }
+
+    fn write(format: Formatting, color: ColorChoice) -> String {
+        let config = OutputConfig {
+            color,
+            ..OutputConfig::default()
+        };
+        let mut buf = Vec::new();
+        write_serialized(&sample(), format, &config, &mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn json_keys_and_strings() {
+        let json = r#"{"a\"b": "x\": y", "c":"\\"}"#;
+        assert_eq!(
+            highlight_json(json, &theme()),
+            format!(
+                "{{{}: {}, {}:{}}}",
+                key(r#""a\"b""#),
+                string(r#""x\": y""#),
+                key(r#""c""#),
+                string(r#""\\""#)
+            )
+        );
+    }
+
+    #[test]
+    fn json_numbers_and_literals() {
+        let json = "[-1.5e-7, 2E+21, 0, true, false, null]";
+        assert_eq!(
+            highlight_json(json, &theme()),
+            format!(
+                "[{}, {}, {}, {}, {}, {}]",
+                number("-1.5e-7"),
+                number("2E+21"),
+                number("0"),
+                literal("true"),
+                literal("false"),
+                literal("null")
+            )
+        );
+    }
+
+    #[test]
+    fn json_pretty() {
+        let json = "{\n  \"k\" : [\n    1\n  ]\n}";
+        assert_eq!(
+            highlight_json(json, &theme()),
+            format!("{{\n  {} : [\n    {}\n  ]\n}}", key("\"k\""), number("1"))
+        );
+    }
+
+    #[test]
+    fn yaml_tokens() {
+        let yaml = "---\nkey: value\nnote: \"a: b\"\n\"quoted: key\": 1\n'single': \
+                    -2.5e3\nlist:\n  - - a\n    - 1\n  - b: true\n    c: ~\nempty: []";
+        let expected = [
+            theme().accent.paint("---", true),
+            format!("{}: {}", key("key"), string("value")),
+            format!("{}: {}", key("note"), string("\"a: b\"")),
+            format!("{}: {}", key("\"quoted: key\""), number("1")),
+            format!("{}: {}", key("'single'"), number("-2.5e3")),
+            format!("{}:", key("list")),
+            format!("  - - {}", string("a")),
+            format!("    - {}", number("1")),
+            format!("  - {}: {}", key("b"), literal("true")),
+            format!("    {}: {}", key("c"), literal("~")),
+            format!("{}: []", key("empty")),
+        ];
+        assert_eq!(highlight_yaml(yaml, &theme()), expected.join("\n"));
+    }
+
+    #[test]
+    fn highlight_preserves_text() {
+        let json = serde_json::to_string(&sample()).unwrap();
+        assert_eq!(strip_ansi(&highlight_json(&json, &theme())), json);
+        let pretty = serde_json::to_string_pretty(&sample()).unwrap();
+        assert_eq!(strip_ansi(&highlight_json(&pretty, &theme())), pretty);
+        let yaml = serde_yaml::to_string(&sample()).unwrap();
+        assert_eq!(strip_ansi(&highlight_yaml(&yaml, &theme())), yaml);
+    }
+
+    #[test]
+    fn uncoloured() {
+        let json = serde_json::to_string(&sample()).unwrap();
+        assert_eq!(write(Formatting::Json, ColorChoice::Never), format!("{}\n", json));
+        let pretty = serde_json::to_string_pretty(&sample()).unwrap();
+        assert_eq!(write(Formatting::JsonPretty, ColorChoice::Never), format!("{}\n", pretty));
+        let yaml = serde_yaml::to_string(&sample()).unwrap();
+        assert_eq!(write(Formatting::Yaml, ColorChoice::Never), format!("{}\n", yaml));
+        assert_ne!(write(Formatting::Json, ColorChoice::Always), format!("{}\n", json));
+    }
+}
@@ -0,0 +1,346 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! JSON Canonicalization Scheme (JCS) according to RFC 8785.
+
+use std::fmt::Write;
+
+use serde::ser::{self, Error as _, Serialize, Serializer};
+use serde_json::{Number, Value};
+
+/// Serializes data into canonical JSON according to RFC 8785 (JSON
+/// Canonicalization Scheme), which produces the same byte sequence for the
+/// same data and thus can be used for hashing and signing:
+/// - object members are sorted by their names compared as UTF-16 code units;
+/// - numbers are represented as IEEE 754 doubles formatted in ECMAScript style (integers with
+///   absolute value above 2^53 lose precision);
+/// - strings are escaped minimally, keeping non-ASCII characters as is;
+/// - no whitespace is produced.
+///
+/// Data containing non-finite floating point numbers (NaN and infinities)
+/// are rejected with an error, as required by RFC 8785 section 3.2.2.2.
+pub fn to_canonical_json(data: &(impl Serialize + ?Sized)) -> Result<String, serde_json::Error> {
+    // `serde_json` silently converts non-finite numbers into `null`, so they
+    // have to be detected before the conversion
+    data.serialize(FiniteCheck)?;
+    let value = serde_json::to_value(data)?;
+    let mut canonical = String::new();
+    write_value(&value, &mut canonical);
+    Ok(canonical)
+}
+
+fn write_value(value: &Value, out: &mut String) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(true) => out.push_str("true"),
+        Value::Bool(false) => out.push_str("false"),
+        Value::Number(number) => write_number(number, out),
+        Value::String(s) => write_string(s, out),
+        Value::Array(items) => {
+            out.push('[');
+            for (no, item) in items.iter().enumerate() {
+                if no > 0 {
+                    out.push(',');
+                }
+                write_value(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(members) => {
+            let mut members = members.iter().collect::<Vec<_>>();
+            members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push('{');
+            for (no, (name, item)) in members.into_iter().enumerate() {
+                if no > 0 {
+                    out.push(',');
+                }
+                write_string(name, out);
+                out.push(':');
+                write_value(item, out);
+            }
+            out.push('}');
+        }
+    }
+}
+
+fn write_string(s: &str, out: &mut String) {
+    out.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{0C}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ch if ch < ' ' => {
+                write!(out, "\\u{:04x}", ch as u32).expect("writing to string never fails")
+            }
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+}
+
+fn write_number(number: &Number, out: &mut String) {
+    let value = number.as_f64().expect("JSON numbers are always convertible to f64");
+    out.push_str(&es6_number(value));
+}
+
+/// Formats finite number according to ECMAScript `Number.prototype.toString`
+/// algorithm, as required by RFC 8785 section 3.2.2.3.
+fn es6_number(value: f64) -> String {
+    // Covers negative zero as well
+    if value == 0.0 {
+        return s!("0");
+    }
+    let sign = if value < 0.0 { "-" } else { "" };
+    // Rust produces the shortest representation which round-trips to the
+    // same double, like ECMAScript does
+    let repr = format!("{:e}", value.abs());
+    let (mantissa, exp) = repr.split_once('e').expect("exponential format always has exponent");
+    let digits = mantissa.replace('.', "");
+    let exp = exp.parse::<i32>().expect("exponent is always an integer");
+    // `k` and `n` follow the notation of the ECMAScript specification
+    let k = digits.len() as i32;
+    let n = exp + 1;
+    let body = if k <= n && n <= 21 {
+        format!("{}{}", digits, "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        let (int, frac) = digits.split_at(n as usize);
+        format!("{}.{}", int, frac)
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{}", "0".repeat(-n as usize), digits)
+    } else {
+        let (first, rest) = digits.split_at(1);
+        let dot = if rest.is_empty() { "" } else { "." };
+        let exp_sign = if n - 1 < 0 { '-' } else { '+' };
+        format!("{}{}{}e{}{}", first, dot, rest, exp_sign, (n - 1).abs())
+    };
+    format!("{}{}", sign, body)
+}
+
+/// Serializer producing no output, which fails on non-finite floating point
+/// numbers.
+struct FiniteCheck;
+
+impl FiniteCheck {
+    fn check(value: f64) -> Result<(), serde_json::Error> {
+        if value.is_finite() {
+            Ok(())
+        } else {
+            Err(serde_json::Error::custom(format!(
+                "non-finite number {} can't be represented in canonical JSON",
+                value
+            )))
+        }
+    }
+}
+
+macro_rules! check_nothing {
+    ($($method:ident($ty:ty)),* $(,)?) => {
+        $(fn $method(self, _: $ty) -> Result<(), serde_json::Error> { Ok(()) })*
+    };
+}
+
+impl Serializer for FiniteCheck {
+    type Ok = ();
+    type Error = serde_json::Error;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    check_nothing!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_unit_struct(&'static str),
+    );
+
+    fn serialize_f32(self, v: f32) -> Result<(), serde_json::Error> { Self::check(v as f64) }
+
+    fn serialize_f64(self, v: f64) -> Result<(), serde_json::Error> { Self::check(v) }
+
+    fn serialize_none(self) -> Result<(), serde_json::Error> { Ok(()) }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), serde_json::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), serde_json::Error> { Ok(()) }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+    ) -> Result<(), serde_json::Error> {
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), serde_json::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), serde_json::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self, serde_json::Error> { Ok(self) }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self, serde_json::Error> { Ok(self) }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self, serde_json::Error> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self, serde_json::Error> {
+        Ok(self)
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self, serde_json::Error> { Ok(self) }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self, serde_json::Error> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self, serde_json::Error> {
+        Ok(self)
+    }
+}
+
+impl ser::SerializeSeq for FiniteCheck {
+    type Ok = ();
+    type Error = serde_json::Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        value.serialize(FiniteCheck)
+    }
+
+    fn end(self) -> Result<(), Self::Error> { Ok(()) }
+}
+
+impl ser::SerializeTuple for FiniteCheck {
+    type Ok = ();
+    type Error = serde_json::Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        value.serialize(FiniteCheck)
+    }
+
+    fn end(self) -> Result<(), Self::Error> { Ok(()) }
+}
+
+impl ser::SerializeTupleStruct for FiniteCheck {
+    type Ok = ();
+    type Error = serde_json::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        value.serialize(FiniteCheck)
+    }
+
+    fn end(self) -> Result<(), Self::Error> { Ok(()) }
+}
+
+impl ser::SerializeTupleVariant for FiniteCheck {
+    type Ok = ();
+    type Error = serde_json::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        value.serialize(FiniteCheck)
+    }
+
+    fn end(self) -> Result<(), Self::Error> { Ok(()) }
+}
+
+impl ser::SerializeMap for FiniteCheck {
+    type Ok = ();
+    type Error = serde_json::Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
+        key.serialize(FiniteCheck)
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        value.serialize(FiniteCheck)
+    }
+
+    fn end(self) -> Result<(), Self::Error> { Ok(()) }
+}
+
+impl ser::SerializeStruct for FiniteCheck {
+    type Ok = ();
+    type Error = serde_json::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        value.serialize(FiniteCheck)
+    }
+
+    fn end(self) -> Result<(), Self::Error> { Ok(()) }
+}
+
+impl ser::SerializeStructVariant for FiniteCheck {
+    type Ok = ();
+    type Error = serde_json::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        value.serialize(FiniteCheck)
+    }
+
+    fn end(self) -> Result<(), Self::Error> { Ok(()) }
+}
@@ -0,0 +1,46 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Helpers for JSON-based output formats.
+
+use serde::Serialize;
+use serde_json::ser::PrettyFormatter;
+use serde_json::Serializer;
+
+use crate::value::Value;
+use crate::OutputError;
+
+/// Serializes data as a pretty-printed JSON using `indent` number of spaces
+/// for each level of indentation.
+pub(crate) fn to_json_pretty(
+    data: &(impl Serialize + ?Sized),
+    indent: usize,
+) -> Result<String, serde_json::Error> {
+    let indent = " ".repeat(indent);
+    let mut buf = Vec::new();
+    let formatter = PrettyFormatter::with_indent(indent.as_bytes());
+    data.serialize(&mut Serializer::with_formatter(&mut buf, formatter))?;
+    Ok(String::from_utf8(buf).expect("serde_json always produces UTF-8 strings"))
+}
+
+/// Serializes map entry as a single-line JSON object, which contains the map
+/// key under `key_field` name followed by the fields of the `value`, as
+/// composed by [`Value::keyed_fields`].
+pub(crate) fn keyed_json_line(
+    key_field: &str,
+    key: &impl Serialize,
+    value: &impl Serialize,
+) -> Result<String, OutputError> {
+    let fields =
+        Value::keyed_fields(key_field, Value::from_serialize(key)?, Value::from_serialize(value)?)?;
+    Ok(serde_json::to_string(&Value::Object(fields))?)
+}
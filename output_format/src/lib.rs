@@ -15,14 +15,56 @@ extern crate amplify;
 #[macro_use]
 extern crate clap;
 
+#[cfg(feature = "arrow")]
+mod arrow;
+mod color;
+mod config;
+mod csv;
+mod error;
+mod highlight;
+mod jcs;
+mod json;
+mod sql;
+mod table;
+mod theme;
+mod tsv;
+mod value;
+mod width;
+mod xlsx;
+mod xml;
+
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt::Display;
 use std::hash::Hash;
+use std::io::{self, IsTerminal, Write};
+use std::iter;
 use std::str::FromStr;
 
-use colored::Colorize;
+pub use output_format_derive::{OutputCompact, OutputFormat};
+use ron::ser::PrettyConfig;
 use serde::Serialize;
 
+#[cfg(feature = "arrow")]
+pub use crate::arrow::{to_record_batch, write_arrow_ipc, write_parquet};
+pub use crate::color::ColorChoice;
+pub use crate::config::OutputConfig;
+pub use crate::csv::csv_escape;
+use crate::csv::write_csv_record;
+pub use crate::error::OutputError;
+pub use crate::highlight::{highlight_json, highlight_yaml};
+pub use crate::jcs::to_canonical_json;
+use crate::json::{keyed_json_line, to_json_pretty};
+#[cfg(feature = "sqlite")]
+pub use crate::sql::write_sqlite;
+use crate::table::Table;
+pub use crate::theme::{Style, Theme, ThemeError};
+pub use crate::tsv::tsv_escape;
+use crate::tsv::tsv_line;
+pub use crate::width::display_width;
+use crate::xlsx::write_xlsx;
+pub use crate::xml::XmlKey;
+use crate::xml::{write_xml_list, write_xml_record};
+
 #[derive(Parser, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display)]
 pub enum Formatting {
     /// Print only data identifier strings (in Bech32m format), one per line
@@ -35,7 +77,8 @@ pub enum Formatting {
     #[display("compact")]
     Compact,
 
-    /// Print tab-separated list of items
+    /// Print tab-separated list of items. Tabs, line breaks and backslashes
+    /// inside the values are escaped as `\t`, `\n`, `\r` and `\\`
     #[display("tab")]
     Tab,
 
@@ -43,13 +86,131 @@ pub enum Formatting {
     #[display("csv")]
     Csv,
 
-    /// Output data as formatted YAML
+    /// Print items as a table with aligned columns, for reading in terminal
+    #[display("table")]
+    Table,
+
+    /// Print items as GitHub-flavoured Markdown table
+    #[display("markdown")]
+    Markdown,
+
+    /// Print items as HTML table
+    #[display("html")]
+    Html,
+
+    /// Print items as LaTeX `tabular` environment
+    #[display("latex")]
+    Latex,
+
+    /// Print items as AsciiDoc table
+    #[display("asciidoc")]
+    AsciiDoc,
+
+    /// Print items as Emacs Org-mode table
+    #[display("org")]
+    Org,
+
+    /// Output data as formatted YAML. Collections are output as a single YAML
+    /// sequence
     #[display("yaml")]
     Yaml,
 
-    /// Output data as JSON
+    /// Output data as JSON. Collections are output as a single JSON array
     #[display("json")]
     Json,
+
+    /// Output data as pretty-printed JSON with indentation
+    #[display("json-pretty")]
+    JsonPretty,
+
+    /// Output data as canonical JSON according to RFC 8785 (JSON
+    /// Canonicalization Scheme), suitable for hashing and signing. The
+    /// canonical JSON is followed by a line feed, which is not a part of it
+    #[display("json-canonical")]
+    JsonCanonical,
+
+    /// Output data as TOML. Items of list-like collections are output as an
+    /// array of tables
+    #[display("toml")]
+    Toml,
+
+    /// Output data as XML document. Collection items are output as item
+    /// elements inside the root element
+    #[display("xml")]
+    Xml,
+
+    /// Output data as Rusty Object Notation (RON) in a single line
+    #[display("ron")]
+    Ron,
+
+    /// Output data as pretty-printed Rusty Object Notation (RON)
+    #[display("ron-pretty")]
+    RonPretty,
+
+    /// Output data in binary CBOR encoding (RFC 8949)
+    #[display("cbor")]
+    Cbor,
+
+    /// Output data in binary MessagePack encoding
+    #[display("msgpack")]
+    MsgPack,
+
+    /// Output data as JSON Lines (NDJSON): a compact JSON object per each
+    /// item of a collection, one per line
+    #[display("jsonl")]
+    JsonLines,
+
+    /// Output data as Excel workbook, with all cells typed as text. Map of
+    /// lists is output with a worksheet per each of the map keys
+    #[display("xlsx")]
+    Xlsx,
+
+    /// Output data as SQL script creating a table and inserting the items
+    /// into it
+    #[display("sql")]
+    Sql,
+}
+
+impl Formatting {
+    /// Detects formats serializing the data structure as a whole, which are
+    /// able to represent empty collections.
+    pub(crate) fn is_structured(self) -> bool {
+        matches!(
+            self,
+            Formatting::Yaml
+                | Formatting::Json
+                | Formatting::JsonPretty
+                | Formatting::JsonCanonical
+                | Formatting::JsonLines
+                | Formatting::Toml
+                | Formatting::Xml
+                | Formatting::Ron
+                | Formatting::RonPretty
+                | Formatting::Cbor
+                | Formatting::MsgPack
+        )
+    }
+
+    /// Detects formats rendering data as a table, which requires all rows to
+    /// be buffered in memory.
+    pub(crate) fn is_table(self) -> bool {
+        matches!(
+            self,
+            Formatting::Table
+                | Formatting::Markdown
+                | Formatting::Html
+                | Formatting::Latex
+                | Formatting::AsciiDoc
+                | Formatting::Org
+                | Formatting::Xlsx
+                | Formatting::Sql
+        )
+    }
+
+    /// Detects formats producing binary data rather than text.
+    pub(crate) fn is_binary(self) -> bool {
+        matches!(self, Formatting::Cbor | Formatting::MsgPack | Formatting::Xlsx)
+    }
 }
 
 impl FromStr for Formatting {
@@ -61,36 +222,266 @@ impl FromStr for Formatting {
             "compact" => Formatting::Compact,
             "tab" => Formatting::Tab,
             "csv" => Formatting::Csv,
+            "table" => Formatting::Table,
+            "markdown" | "md" => Formatting::Markdown,
+            "html" => Formatting::Html,
+            "latex" | "tex" => Formatting::Latex,
+            "asciidoc" | "adoc" => Formatting::AsciiDoc,
+            "org" | "org-mode" => Formatting::Org,
             "yaml" => Formatting::Yaml,
             "json" => Formatting::Json,
+            "json-pretty" | "pretty-json" => Formatting::JsonPretty,
+            "json-canonical" | "canonical-json" | "jcs" => Formatting::JsonCanonical,
+            "toml" => Formatting::Toml,
+            "xml" => Formatting::Xml,
+            "ron" => Formatting::Ron,
+            "ron-pretty" | "pretty-ron" => Formatting::RonPretty,
+            "cbor" => Formatting::Cbor,
+            "msgpack" | "messagepack" => Formatting::MsgPack,
+            "jsonl" | "ndjson" | "json-lines" => Formatting::JsonLines,
+            "xlsx" | "excel" => Formatting::Xlsx,
+            "sql" => Formatting::Sql,
             _ => Err("Unknown format name")?,
         })
     }
 }
 
+/// Type of the values in a column of the tabular output, used by the
+/// typed formats (Arrow and Parquet) to build the schema independently of
+/// the data.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Default)]
+pub enum ColumnType {
+    /// Arbitrary text; the cells are stored as they are printed.
+    #[default]
+    String,
+    /// Boolean values printed as `true` or `false`.
+    Boolean,
+    /// Signed integers fitting into 64 bits.
+    Int64,
+    /// Unsigned integers fitting into 64 bits.
+    UInt64,
+    /// Floating point numbers.
+    Float64,
+}
+
 pub trait OutputCompact {
     fn output_compact(&self) -> String;
 }
 
 pub trait OutputFormat: OutputCompact + Serialize {
+    /// Prints data to the standard output. This is a thin wrapper around
+    /// [`OutputFormat::output_try_print`] which reports empty collections and
+    /// output errors to the standard error instead of returning them. If the
+    /// output is closed by the reader (for instance when piped into `head`)
+    /// the printing stops silently.
     fn output_print(&self, format: Formatting) {
-        match format {
-            Formatting::Id => println!("{}", self.output_id_string()),
-            Formatting::Compact => println!("{}", self.output_compact()),
-            Formatting::Tab => println!("{}", self.output_fields().join("\t")),
-            Formatting::Csv => println!("{}", self.output_fields().join(",")),
-            Formatting::Yaml => {
-                println!("{}", serde_yaml::to_string(self).unwrap_or_default())
+        self.output_print_with(format, &OutputConfig::default())
+    }
+
+    /// Prints data to the standard output using the provided configuration,
+    /// reporting errors to the standard error like
+    /// [`OutputFormat::output_print`] does.
+    fn output_print_with(&self, format: Formatting, config: &OutputConfig) {
+        let colorize = config.color.resolve(io::stderr().is_terminal()) == ColorChoice::Always;
+        match self.output_try_print_with(format, config) {
+            Ok(()) | Err(OutputError::BrokenPipe) => {}
+            Err(OutputError::EmptyCollection) => {
+                eprintln!("{}", config.theme.empty.paint("No items", colorize))
             }
-            Formatting::Json => {
-                println!("{}", serde_json::to_string(self).unwrap_or_default())
+            Err(err) => eprintln!("{}", config.theme.error.paint(&err.to_string(), colorize)),
+        }
+    }
+
+    /// Prints data to the standard output, returning error if the data can't
+    /// be serialized, the collection is empty or the output has failed.
+    fn output_try_print(&self, format: Formatting) -> Result<(), OutputError> {
+        self.output_try_print_with(format, &OutputConfig::default())
+    }
+
+    /// Prints data to the standard output using the provided configuration,
+    /// returning error if the data can't be serialized, the collection is
+    /// empty or the output has failed.
+    fn output_try_print_with(
+        &self,
+        format: Formatting,
+        config: &OutputConfig,
+    ) -> Result<(), OutputError> {
+        if format.is_binary() && !config.force_binary && io::stdout().is_terminal() {
+            return Err(OutputError::BinaryToTerminal(format));
+        }
+        let config = OutputConfig {
+            color: config.color.resolve(io::stdout().is_terminal()),
+            ..config.clone()
+        };
+        let stdout = io::stdout();
+        let mut lock = stdout.lock();
+        self.output_write_with(format, &config, &mut lock)?;
+        lock.flush()?;
+        Ok(())
+    }
+
+    /// Writes data in the given format into an arbitrary writer, which may
+    /// be a buffer, a file, a socket etc.
+    fn output_write(&self, format: Formatting, f: &mut impl io::Write) -> Result<(), OutputError> {
+        self.output_write_with(format, &OutputConfig::default(), f)
+    }
+
+    /// Writes data in the given format into an arbitrary writer using the
+    /// provided configuration.
+    fn output_write_with(
+        &self,
+        format: Formatting,
+        config: &OutputConfig,
+        f: &mut impl io::Write,
+    ) -> Result<(), OutputError> {
+        match format {
+            Formatting::Id => writeln!(f, "{}", self.output_id_string())?,
+            Formatting::Compact => writeln!(f, "{}", self.output_compact())?,
+            Formatting::Tab => writeln!(f, "{}", tsv_line(self.output_fields()))?,
+            Formatting::Csv => write_csv_record(f, self.output_fields(), config)?,
+            Formatting::Table
+            | Formatting::Markdown
+            | Formatting::Html
+            | Formatting::Latex
+            | Formatting::AsciiDoc
+            | Formatting::Org
+            | Formatting::Xlsx
+            | Formatting::Sql => {
+                let mut table = Table::new(Self::output_headers());
+                table.push_row(self.output_fields());
+                table.write(format, config, f)?
             }
+            Formatting::JsonLines => writeln!(f, "{}", serde_json::to_string(self)?)?,
+            Formatting::Xml => write_xml_record(f, self, config)?,
+            Formatting::Yaml
+            | Formatting::Json
+            | Formatting::JsonPretty
+            | Formatting::JsonCanonical
+            | Formatting::Toml
+            | Formatting::Ron
+            | Formatting::RonPretty
+            | Formatting::Cbor
+            | Formatting::MsgPack => write_serialized(self, format, config, f)?,
         }
+        Ok(())
     }
 
     fn output_headers() -> Vec<String>;
     fn output_id_string(&self) -> String;
     fn output_fields(&self) -> Vec<String>;
+
+    /// Returns rows of the tabular representation of the data, matching
+    /// [`OutputFormat::output_headers`]. A single record is represented by
+    /// its [`OutputFormat::output_fields`]; collections have a row per item,
+    /// which is prefixed with the item key for maps.
+    fn output_rows(&self) -> Vec<Vec<String>> { vec![self.output_fields()] }
+
+    /// Returns types of the columns matching [`OutputFormat::output_headers`].
+    /// Empty cells of non-string columns are treated as missing values. By
+    /// default all the columns hold strings.
+    fn output_column_types() -> Vec<ColumnType> {
+        vec![ColumnType::String; Self::output_headers().len()]
+    }
+}
+
+/// Writes the data structure as a whole using one of serde-based formats.
+fn write_serialized(
+    data: &(impl Serialize + ?Sized),
+    format: Formatting,
+    config: &OutputConfig,
+    f: &mut impl io::Write,
+) -> Result<(), OutputError> {
+    match format {
+        Formatting::Yaml => {
+            let yaml = serde_yaml::to_string(data)?;
+            if config.colorize() {
+                writeln!(f, "{}", highlight_yaml(&yaml, &config.theme))?
+            } else {
+                writeln!(f, "{}", yaml)?
+            }
+        }
+        Formatting::Json | Formatting::JsonPretty => {
+            let json = match format {
+                Formatting::Json => serde_json::to_string(data)?,
+                _ => to_json_pretty(data, config.json_indent)?,
+            };
+            if config.colorize() {
+                writeln!(f, "{}", highlight_json(&json, &config.theme))?
+            } else {
+                writeln!(f, "{}", json)?
+            }
+        }
+        Formatting::JsonCanonical => writeln!(f, "{}", to_canonical_json(data)?)?,
+        // TOML serializer terminates the document with a line feed itself
+        Formatting::Toml => write!(f, "{}", toml::to_string(data)?)?,
+        Formatting::Ron => writeln!(f, "{}", ron::to_string(data)?)?,
+        Formatting::RonPretty => {
+            writeln!(f, "{}", ron::ser::to_string_pretty(data, PrettyConfig::default())?)?
+        }
+        Formatting::Cbor => ciborium::ser::into_writer(data, &mut *f).map_err(|err| match err {
+            // Keep broken pipe distinguishable from serialization failures
+            ciborium::ser::Error::Io(err) => OutputError::from(err),
+            err => OutputError::Cbor(err),
+        })?,
+        Formatting::MsgPack => f.write_all(&rmp_serde::to_vec_named(data)?)?,
+        _ => return Err(OutputError::UnsupportedFormat(format)),
+    }
+    Ok(())
+}
+
+/// Writes a header line followed by all items of a list-like collection.
+/// For structured formats (YAML, JSON, TOML, XML, RON, CBOR and MessagePack)
+/// the whole collection is serialized as a single sequence, which is empty
+/// for an empty collection; JSON Lines has a line per item.
+fn write_list<'a, T>(
+    collection: &impl Serialize,
+    list: impl ExactSizeIterator<Item = &'a T>,
+    format: Formatting,
+    config: &OutputConfig,
+    f: &mut impl io::Write,
+) -> Result<(), OutputError>
+where
+    T: OutputFormat + 'a,
+{
+    match format {
+        Formatting::Yaml
+        | Formatting::Json
+        | Formatting::JsonPretty
+        | Formatting::JsonCanonical
+        | Formatting::Ron
+        | Formatting::RonPretty
+        | Formatting::Cbor
+        | Formatting::MsgPack => {
+            return write_serialized(collection, format, config, f);
+        }
+        Formatting::Toml => {
+            let table = BTreeMap::from([(config.toml_list_key.as_str(), collection)]);
+            return write_serialized(&table, format, config, f);
+        }
+        Formatting::Xml => return write_xml_list(f, list.map(|t| (None, t)), config),
+        Formatting::JsonLines => {
+            for t in list {
+                writeln!(f, "{}", serde_json::to_string(t)?)?;
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+    if list.len() == 0 {
+        return Err(OutputError::EmptyCollection);
+    }
+    let headers = T::output_headers();
+    if format.is_table() {
+        let mut table = Table::new(headers);
+        list.for_each(|t| table.push_row(t.output_fields()));
+        return table.write(format, config, f);
+    }
+    if format == Formatting::Tab {
+        writeln!(f, "{}", config.paint(&tsv_line(&headers), config.theme.header))?;
+    } else if format == Formatting::Csv {
+        write_csv_record(f, &headers, config)?;
+    }
+    list.into_iter().try_for_each(|t| t.output_write_with(format, config, f))
 }
 
 #[doc(hidden)]
@@ -103,28 +494,26 @@ where T: OutputCompact
 impl<T> OutputFormat for Vec<T>
 where T: OutputFormat
 {
-    fn output_print(&self, format: Formatting) {
-        if self.is_empty() {
-            eprintln!("{}", "No items".red());
-            return;
-        }
-        let headers = T::output_headers();
-        if format == Formatting::Tab {
-            println!("{}", headers.join("\t").bright_green())
-        } else if format == Formatting::Csv {
-            println!("{}", headers.join(","))
-        }
-        self.iter().for_each(|t| t.output_print(format));
+    fn output_write_with(
+        &self,
+        format: Formatting,
+        config: &OutputConfig,
+        f: &mut impl io::Write,
+    ) -> Result<(), OutputError> {
+        write_list(self, self.iter(), format, config, f)
     }
 
     #[doc(hidden)]
     fn output_id_string(&self) -> String { unreachable!() }
 
-    #[doc(hidden)]
-    fn output_headers() -> Vec<String> { unreachable!() }
+    fn output_headers() -> Vec<String> { T::output_headers() }
 
     #[doc(hidden)]
     fn output_fields(&self) -> Vec<String> { unreachable!() }
+
+    fn output_rows(&self) -> Vec<Vec<String>> { self.iter().map(T::output_fields).collect() }
+
+    fn output_column_types() -> Vec<ColumnType> { T::output_column_types() }
 }
 
 #[doc(hidden)]
@@ -137,28 +526,26 @@ where T: OutputCompact
 impl<T> OutputFormat for BTreeSet<T>
 where T: OutputFormat + Ord + Eq + Hash
 {
-    fn output_print(&self, format: Formatting) {
-        if self.is_empty() {
-            eprintln!("{}", "No items".red());
-            return;
-        }
-        let headers = T::output_headers();
-        if format == Formatting::Tab {
-            println!("{}", headers.join("\t").bright_green())
-        } else if format == Formatting::Csv {
-            println!("{}", headers.join(","))
-        }
-        self.iter().for_each(|t| t.output_print(format));
+    fn output_write_with(
+        &self,
+        format: Formatting,
+        config: &OutputConfig,
+        f: &mut impl io::Write,
+    ) -> Result<(), OutputError> {
+        write_list(self, self.iter(), format, config, f)
     }
 
     #[doc(hidden)]
     fn output_id_string(&self) -> String { unreachable!() }
 
-    #[doc(hidden)]
-    fn output_headers() -> Vec<String> { unreachable!() }
+    fn output_headers() -> Vec<String> { T::output_headers() }
 
     #[doc(hidden)]
     fn output_fields(&self) -> Vec<String> { unreachable!() }
+
+    fn output_rows(&self) -> Vec<Vec<String>> { self.iter().map(T::output_fields).collect() }
+
+    fn output_column_types() -> Vec<ColumnType> { T::output_column_types() }
 }
 
 #[doc(hidden)]
@@ -171,28 +558,26 @@ where T: OutputCompact
 impl<T> OutputFormat for HashSet<T>
 where T: OutputFormat + Eq + Hash
 {
-    fn output_print(&self, format: Formatting) {
-        if self.is_empty() {
-            eprintln!("{}", "No items".red());
-            return;
-        }
-        let headers = T::output_headers();
-        if format == Formatting::Tab {
-            println!("{}", headers.join("\t").bright_green())
-        } else if format == Formatting::Csv {
-            println!("{}", headers.join(","))
-        }
-        self.iter().for_each(|t| t.output_print(format));
+    fn output_write_with(
+        &self,
+        format: Formatting,
+        config: &OutputConfig,
+        f: &mut impl io::Write,
+    ) -> Result<(), OutputError> {
+        write_list(self, self.iter(), format, config, f)
     }
 
     #[doc(hidden)]
     fn output_id_string(&self) -> String { unreachable!() }
 
-    #[doc(hidden)]
-    fn output_headers() -> Vec<String> { unreachable!() }
+    fn output_headers() -> Vec<String> { T::output_headers() }
 
     #[doc(hidden)]
     fn output_fields(&self) -> Vec<String> { unreachable!() }
+
+    fn output_rows(&self) -> Vec<Vec<String>> { self.iter().map(T::output_fields).collect() }
+
+    fn output_column_types() -> Vec<ColumnType> { T::output_column_types() }
 }
 
 impl<K, V> OutputCompact for HashMap<K, V>
@@ -208,41 +593,76 @@ where
     K: Clone + Display + std::hash::Hash + Eq + Serialize,
     V: OutputFormat + Serialize,
 {
-    fn output_print(&self, format: Formatting) {
-        if self.is_empty() {
-            eprintln!("{}", "No items".red());
-            return;
+    fn output_write_with(
+        &self,
+        format: Formatting,
+        config: &OutputConfig,
+        f: &mut impl io::Write,
+    ) -> Result<(), OutputError> {
+        if self.is_empty() && !format.is_structured() {
+            return Err(OutputError::EmptyCollection);
         }
         let headers = Self::output_headers();
         if format == Formatting::Tab {
-            println!("{}", headers.join("\t").bright_green())
+            writeln!(f, "{}", config.paint(&tsv_line(&headers), config.theme.header))?;
         } else if format == Formatting::Csv {
-            println!("{}", headers.join(","))
+            write_csv_record(f, &headers, config)?;
         }
 
         match format {
-            Formatting::Yaml => {
-                println!("{}", serde_yaml::to_string(self).unwrap_or_default())
+            format if format.is_table() => {
+                let mut table = Table::new(headers);
+                self.output_rows().into_iter().for_each(|row| table.push_row(row));
+                table.write(format, config, f)?;
             }
 
-            Formatting::Json => {
-                println!("{}", serde_json::to_string(self).unwrap_or_default())
+            Formatting::Yaml
+            | Formatting::Json
+            | Formatting::JsonPretty
+            | Formatting::JsonCanonical
+            | Formatting::Toml
+            | Formatting::Ron
+            | Formatting::RonPretty
+            | Formatting::Cbor
+            | Formatting::MsgPack => {
+                write_serialized(self, format, config, f)?;
             }
 
-            _ => self.iter().for_each(|(id, rec)| match format {
-                Formatting::Id => println!("{}", id),
+            Formatting::Xml => {
+                write_xml_list(
+                    f,
+                    self.iter().map(|(id, rec)| (Some(id.to_string()), rec)),
+                    config,
+                )?;
+            }
+
+            Formatting::JsonLines => {
+                for (id, rec) in self {
+                    writeln!(f, "{}", keyed_json_line(&config.jsonl_key_field, id, rec)?)?;
+                }
+            }
+
+            _ => self.iter().try_for_each(|(id, rec)| match format {
+                Formatting::Id => writeln!(f, "{}", id),
                 Formatting::Compact => {
-                    println!("{}#{}", rec.output_compact(), id)
+                    writeln!(f, "{}#{}", rec.output_compact(), id)
                 }
                 Formatting::Tab => {
-                    println!("{}\t{}", id, rec.output_fields().join("\t"))
-                }
-                Formatting::Csv => {
-                    println!("{},{}", id, rec.output_fields().join(","))
+                    writeln!(
+                        f,
+                        "{}",
+                        tsv_line(iter::once(id.to_string()).chain(rec.output_fields()))
+                    )
                 }
+                Formatting::Csv => write_csv_record(
+                    f,
+                    iter::once(id.to_string()).chain(rec.output_fields()),
+                    config,
+                ),
                 _ => unreachable!(),
-            }),
+            })?,
         }
+        Ok(())
     }
 
     fn output_headers() -> Vec<String> {
@@ -251,11 +671,23 @@ where
         vec
     }
 
+    fn output_column_types() -> Vec<ColumnType> {
+        let mut vec = vec![ColumnType::String];
+        vec.extend(V::output_column_types());
+        vec
+    }
+
     #[doc(hidden)]
     fn output_id_string(&self) -> String { unreachable!() }
 
     #[doc(hidden)]
     fn output_fields(&self) -> Vec<String> { unreachable!() }
+
+    fn output_rows(&self) -> Vec<Vec<String>> {
+        self.iter()
+            .map(|(id, rec)| iter::once(id.to_string()).chain(rec.output_fields()).collect())
+            .collect()
+    }
 }
 
 impl<K, V> OutputCompact for BTreeMap<K, Vec<V>>
@@ -271,44 +703,99 @@ where
     K: Clone + Display + Ord + Serialize,
     V: OutputFormat + Ord + Serialize,
 {
-    fn output_print(&self, format: Formatting) {
-        if self.values().all(Vec::is_empty) {
-            eprintln!("{}", "No items".red());
-            return;
+    fn output_write_with(
+        &self,
+        format: Formatting,
+        config: &OutputConfig,
+        f: &mut impl io::Write,
+    ) -> Result<(), OutputError> {
+        if self.values().all(Vec::is_empty) && !format.is_structured() {
+            return Err(OutputError::EmptyCollection);
         }
         let headers = Self::output_headers();
         if format == Formatting::Tab {
-            println!("{}", headers.join("\t").bright_green())
+            writeln!(f, "{}", config.paint(&tsv_line(&headers), config.theme.header))?;
         } else if format == Formatting::Csv {
-            println!("{}", headers.join(","))
+            write_csv_record(f, &headers, config)?;
         }
 
         match format {
-            Formatting::Yaml => {
-                println!("{}", serde_yaml::to_string(self).unwrap_or_default())
+            Formatting::Xlsx => {
+                let sheets = self
+                    .iter()
+                    .map(|(id, details)| {
+                        let mut table = Table::new(V::output_headers());
+                        details.iter().for_each(|rec| table.push_row(rec.output_fields()));
+                        (id.to_string(), table)
+                    })
+                    .collect::<Vec<_>>();
+                write_xlsx(f, sheets.iter().map(|(name, table)| (name.clone(), table)))?;
+            }
+
+            format if format.is_table() => {
+                let mut table = Table::new(headers);
+                self.output_rows().into_iter().for_each(|row| table.push_row(row));
+                table.write(format, config, f)?;
+            }
+
+            Formatting::Yaml
+            | Formatting::Json
+            | Formatting::JsonPretty
+            | Formatting::JsonCanonical
+            | Formatting::Toml
+            | Formatting::Ron
+            | Formatting::RonPretty
+            | Formatting::Cbor
+            | Formatting::MsgPack => {
+                write_serialized(self, format, config, f)?;
             }
 
-            Formatting::Json => {
-                println!("{}", serde_json::to_string(self).unwrap_or_default())
+            Formatting::Xml => {
+                let items = self.iter().flat_map(|(id, details)| {
+                    details.iter().map(move |rec| (Some(id.to_string()), rec))
+                });
+                write_xml_list(f, items, config)?;
+            }
+
+            Formatting::JsonLines => {
+                for (id, details) in self {
+                    for rec in details {
+                        writeln!(f, "{}", keyed_json_line(&config.jsonl_key_field, id, rec)?)?;
+                    }
+                }
             }
 
-            _ => self.iter().for_each(|(id, details)| {
-                let id = id.to_string().as_str().bright_white();
-                details.iter().for_each(|rec| match format {
-                    Formatting::Id => println!("{}", id),
+            _ => self.iter().try_for_each(|(id, details)| {
+                let id = id.to_string();
+                details.iter().try_for_each(|rec| match format {
+                    Formatting::Id => writeln!(f, "{}", config.paint(&id, config.theme.id)),
                     Formatting::Compact => {
-                        println!("{}#{}", rec.output_compact(), id)
+                        writeln!(
+                            f,
+                            "{}#{}",
+                            rec.output_compact(),
+                            config.paint(&id, config.theme.id)
+                        )
                     }
                     Formatting::Tab => {
-                        println!("{}\t{}", id, rec.output_fields().join("\t"))
-                    }
-                    Formatting::Csv => {
-                        println!("{},{}", id, rec.output_fields().join(","))
+                        let fields = tsv_line(rec.output_fields());
+                        writeln!(
+                            f,
+                            "{}\t{}",
+                            config.paint(&tsv_escape(&id), config.theme.id),
+                            fields
+                        )
                     }
+                    Formatting::Csv => write_csv_record(
+                        f,
+                        iter::once(id.clone()).chain(rec.output_fields()),
+                        config,
+                    ),
                     _ => unreachable!(),
                 })
-            }),
+            })?,
         }
+        Ok(())
     }
 
     fn output_headers() -> Vec<String> {
@@ -317,9 +804,25 @@ where
         vec
     }
 
+    fn output_column_types() -> Vec<ColumnType> {
+        let mut vec = vec![ColumnType::String];
+        vec.extend(V::output_column_types());
+        vec
+    }
+
     #[doc(hidden)]
     fn output_id_string(&self) -> String { unreachable!() }
 
     #[doc(hidden)]
     fn output_fields(&self) -> Vec<String> { unreachable!() }
+
+    fn output_rows(&self) -> Vec<Vec<String>> {
+        self.iter()
+            .flat_map(|(id, details)| {
+                details
+                    .iter()
+                    .map(move |rec| iter::once(id.to_string()).chain(rec.output_fields()).collect())
+            })
+            .collect()
+    }
 }
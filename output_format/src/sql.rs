@@ -0,0 +1,116 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! SQL dump output, optionally loaded directly into SQLite database.
+
+use std::io;
+#[cfg(feature = "sqlite")]
+use std::path::Path;
+
+use crate::table::Table;
+use crate::OutputConfig;
+#[cfg(feature = "sqlite")]
+use crate::{OutputError, OutputFormat};
+
+/// Writes the table as SQL script creating a table with a text column per
+/// header and inserting a row per each of the table rows. The script is
+/// wrapped into a transaction.
+pub(crate) fn write_sql(
+    f: &mut impl io::Write,
+    table: &Table,
+    config: &OutputConfig,
+) -> io::Result<()> {
+    let name = sql_ident(&config.sql_table);
+    let columns = table.headers().iter().map(|header| sql_ident(header)).collect::<Vec<_>>();
+
+    writeln!(f, "BEGIN TRANSACTION;")?;
+    writeln!(f, "{};", create_table(&name, &columns))?;
+    for row in table.rows() {
+        let values = (0..columns.len())
+            .map(|no| row.get(no).map(|cell| sql_string(cell)).unwrap_or_else(|| s!("NULL")))
+            .collect::<Vec<_>>();
+        writeln!(
+            f,
+            "INSERT INTO {} ({}) VALUES ({});",
+            name,
+            columns.join(", "),
+            values.join(", ")
+        )?;
+    }
+    writeln!(f, "COMMIT;")
+}
+
+/// Writes data into SQLite database file, creating the file if it does not
+/// exist. The data are put into a new table named after
+/// [`OutputConfig::sql_table`], which must not exist in the database. The
+/// rows are inserted with a prepared statement inside a single transaction,
+/// so either all the data are written or none.
+#[cfg(feature = "sqlite")]
+pub fn write_sqlite<D: OutputFormat>(
+    data: &D,
+    path: impl AsRef<Path>,
+    config: &OutputConfig,
+) -> Result<(), OutputError> {
+    let rows = data.output_rows();
+    if rows.is_empty() {
+        return Err(OutputError::EmptyCollection);
+    }
+    let name = sql_ident(&config.sql_table);
+    let columns = D::output_headers().iter().map(|header| sql_ident(header)).collect::<Vec<_>>();
+
+    let mut connection = rusqlite::Connection::open(path)?;
+    let transaction = connection.transaction()?;
+    transaction.execute(&create_table(&name, &columns), [])?;
+    {
+        let placeholders = (1..=columns.len()).map(|no| format!("?{}", no)).collect::<Vec<_>>();
+        let mut insert = transaction.prepare(&format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            name,
+            columns.join(", "),
+            placeholders.join(", ")
+        ))?;
+        for row in &rows {
+            insert.execute(rusqlite::params_from_iter((0..columns.len()).map(|no| row.get(no))))?;
+        }
+    }
+    transaction.commit()?;
+    Ok(())
+}
+
+/// Composes `CREATE TABLE` statement (without the terminating semicolon)
+/// with a text column per each of the quoted column names.
+fn create_table(name: &str, columns: &[String]) -> String {
+    let definitions = columns.iter().map(|column| format!("  {} TEXT", column));
+    format!("CREATE TABLE {} (\n{}\n)", name, definitions.collect::<Vec<_>>().join(",\n"))
+}
+
+/// Quotes SQL identifier, doubling the quotes inside it.
+fn sql_ident(name: &str) -> String { format!("\"{}\"", name.replace('"', "\"\"")) }
+
+/// Quotes SQL string literal, doubling the quotes inside it.
+fn sql_string(value: &str) -> String { format!("'{}'", value.replace('\'', "''")) }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn quoting() {
+        assert_eq!(sql_ident("items"), r#""items""#);
+        assert_eq!(sql_ident(r#"say "hi""#), r#""say ""hi""""#);
+        assert_eq!(sql_ident("it's"), r#""it's""#);
+        assert_eq!(sql_string("rent"), "'rent'");
+        assert_eq!(sql_string("it's 'ok'"), "'it''s ''ok'''");
+        assert_eq!(sql_string(r#"say "hi""#), r#"'say "hi"'"#);
+        assert_eq!(sql_string(""), "''");
+    }
+}
@@ -0,0 +1,463 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Tabular data buffered in memory and rendered as a table in one of the
+//! supported table formats.
+
+use std::io;
+
+use crate::sql::write_sql;
+use crate::theme::Style;
+use crate::xlsx::write_xlsx;
+use crate::{display_width, tsv_escape, Formatting, OutputConfig, OutputError};
+
+/// Table made of a header row and data rows, all of which are kept in memory
+/// such that the column widths can be computed before the output.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub(crate) struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: Vec<String>) -> Table {
+        Table {
+            headers,
+            rows: vec![],
+        }
+    }
+
+    pub fn push_row(&mut self, row: impl IntoIterator<Item = String>) {
+        self.rows.push(row.into_iter().collect())
+    }
+
+    pub fn headers(&self) -> &[String] { &self.headers }
+
+    pub fn rows(&self) -> &[Vec<String>] { &self.rows }
+
+    /// Computes width of each column, which is the maximal width of the
+    /// column header and the cells in the column.
+    fn column_widths(&self) -> Vec<usize> {
+        let columns = self.rows.iter().map(Vec::len).chain([self.headers.len()]).max();
+        let mut widths = vec![0; columns.unwrap_or_default()];
+        for row in self.rows.iter().chain([&self.headers]) {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell_width(cell));
+            }
+        }
+        widths
+    }
+
+    /// Writes the table using the given table `format`.
+    pub fn write(
+        &self,
+        format: Formatting,
+        config: &OutputConfig,
+        f: &mut impl io::Write,
+    ) -> Result<(), OutputError> {
+        match format {
+            Formatting::Table => self.write_aligned(f, config)?,
+            Formatting::Markdown => self.write_markdown(f)?,
+            Formatting::Html => self.write_html(f, config)?,
+            Formatting::Latex => self.write_latex(f)?,
+            Formatting::AsciiDoc => self.write_asciidoc(f)?,
+            Formatting::Org => self.write_org(f)?,
+            Formatting::Xlsx => write_xlsx(f, [(config.xlsx_sheet.clone(), self)])?,
+            Formatting::Sql => write_sql(f, self, config)?,
+            _ => return Err(OutputError::UnsupportedFormat(format)),
+        }
+        Ok(())
+    }
+
+    /// Writes the table aligned by columns, with or without the box-drawing
+    /// borders, depending on the `config`.
+    fn write_aligned(&self, f: &mut impl io::Write, config: &OutputConfig) -> io::Result<()> {
+        let widths = self.column_widths();
+        let theme = &config.theme;
+        let header_styles = vec![theme.header; widths.len()];
+        let styles = (0..widths.len())
+            .map(|no| self.headers.get(no).map(|header| theme.column(header)).unwrap_or_default())
+            .collect::<Vec<_>>();
+        if config.table_borders {
+            write_border(f, &widths, ['┌', '┬', '┐'], config)?;
+            write_aligned_row(f, &self.headers, &widths, &header_styles, config)?;
+            write_border(f, &widths, ['├', '┼', '┤'], config)?;
+            for row in &self.rows {
+                write_aligned_row(f, row, &widths, &styles, config)?;
+            }
+            write_border(f, &widths, ['└', '┴', '┘'], config)
+        } else {
+            write_aligned_row(f, &self.headers, &widths, &header_styles, config)?;
+            for row in &self.rows {
+                write_aligned_row(f, row, &widths, &styles, config)?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Writes the table in GitHub-flavoured Markdown.
+    fn write_markdown(&self, f: &mut impl io::Write) -> io::Result<()> {
+        let columns = self.column_widths().len();
+        let line = |row: &[String]| {
+            let cells = (0..columns)
+                .map(|no| markdown_escape(row.get(no).map(String::as_str).unwrap_or_default()))
+                .collect::<Vec<_>>();
+            format!("| {} |", cells.join(" | "))
+        };
+        writeln!(f, "{}", line(&self.headers))?;
+        writeln!(f, "|{}", " --- |".repeat(columns))?;
+        for row in &self.rows {
+            writeln!(f, "{}", line(row))?;
+        }
+        Ok(())
+    }
+
+    /// Writes the table as HTML `<table>` element, which may be wrapped into
+    /// a standalone HTML document depending on the `config`.
+    fn write_html(&self, f: &mut impl io::Write, config: &OutputConfig) -> io::Result<()> {
+        if config.html_standalone {
+            f.write_all(HTML_HEAD.as_bytes())?;
+        }
+        match &config.html_table_class {
+            Some(class) => writeln!(f, "<table class=\"{}\">", html_escape(class))?,
+            None => writeln!(f, "<table>")?,
+        }
+        writeln!(f, "  <thead>")?;
+        write_html_row(f, &self.headers, "th")?;
+        writeln!(f, "  </thead>")?;
+        writeln!(f, "  <tbody>")?;
+        for row in &self.rows {
+            write_html_row(f, row, "td")?;
+        }
+        writeln!(f, "  </tbody>")?;
+        writeln!(f, "</table>")?;
+        if config.html_standalone {
+            f.write_all(HTML_TAIL.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Writes the table as LaTeX `tabular` environment with left-aligned
+    /// columns.
+    fn write_latex(&self, f: &mut impl io::Write) -> io::Result<()> {
+        let columns = self.column_widths().len();
+        let line = |row: &[String]| {
+            let cells = (0..columns)
+                .map(|no| latex_escape(row.get(no).map(String::as_str).unwrap_or_default()))
+                .collect::<Vec<_>>();
+            format!("{} \\\\", cells.join(" & "))
+        };
+        writeln!(f, "\\begin{{tabular}}{{{}}}", "l".repeat(columns))?;
+        writeln!(f, "\\hline")?;
+        writeln!(f, "{}", line(&self.headers))?;
+        writeln!(f, "\\hline")?;
+        for row in &self.rows {
+            writeln!(f, "{}", line(row))?;
+        }
+        writeln!(f, "\\hline")?;
+        writeln!(f, "\\end{{tabular}}")
+    }
+
+    /// Writes the table in AsciiDoc, with the first row marked as a header.
+    fn write_asciidoc(&self, f: &mut impl io::Write) -> io::Result<()> {
+        let columns = self.column_widths().len();
+        let line = |row: &[String]| {
+            (0..columns)
+                .map(|no| asciidoc_escape(row.get(no).map(String::as_str).unwrap_or_default()))
+                .map(|cell| if cell.is_empty() { s!("|") } else { format!("| {}", cell) })
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        writeln!(f, "[options=\"header\"]")?;
+        writeln!(f, "|===")?;
+        writeln!(f, "{}", line(&self.headers))?;
+        writeln!(f)?;
+        for row in &self.rows {
+            writeln!(f, "{}", line(row))?;
+        }
+        writeln!(f, "|===")
+    }
+
+    /// Writes the table in Emacs Org-mode, with columns aligned the same way
+    /// Org-mode aligns them.
+    fn write_org(&self, f: &mut impl io::Write) -> io::Result<()> {
+        let escape = |row: &[String]| row.iter().map(|cell| org_escape(cell)).collect::<Vec<_>>();
+        let headers = escape(&self.headers);
+        let rows = self.rows.iter().map(|row| escape(row)).collect::<Vec<_>>();
+        let columns = rows.iter().map(Vec::len).chain([headers.len()]).max().unwrap_or_default();
+        let mut widths = vec![0; columns];
+        for row in rows.iter().chain([&headers]) {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(display_width(cell));
+            }
+        }
+        let line = |row: &[String]| {
+            let cells = widths
+                .iter()
+                .enumerate()
+                .map(|(no, width)| {
+                    let cell = row.get(no).map(String::as_str).unwrap_or_default();
+                    format!("{}{}", cell, " ".repeat(width - display_width(cell)))
+                })
+                .collect::<Vec<_>>();
+            format!("| {} |", cells.join(" | "))
+        };
+        writeln!(f, "{}", line(&headers))?;
+        let rules = widths.iter().map(|width| "-".repeat(width + 2)).collect::<Vec<_>>();
+        writeln!(f, "|{}|", rules.join("+"))?;
+        for row in &rows {
+            writeln!(f, "{}", line(row))?;
+        }
+        Ok(())
+    }
+}
+
+const HTML_HEAD: &str = "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<style>
+table { border-collapse: collapse; font-family: sans-serif; }
+th, td { border: 1px solid #ccc; padding: 0.25em 0.75em; text-align: left; vertical-align: top; }
+th { background-color: #f2f2f2; }
+tbody tr:nth-child(even) { background-color: #fafafa; }
+</style>
+</head>
+<body>
+";
+
+const HTML_TAIL: &str = "</body>
+</html>
+";
+
+fn write_html_row(f: &mut impl io::Write, row: &[String], tag: &str) -> io::Result<()> {
+    write!(f, "    <tr>")?;
+    for cell in row {
+        write!(
+            f,
+            "<{}>{}</{}>",
+            tag,
+            html_escape(cell).replace("\r\n", "<br>").replace(['\r', '\n'], "<br>"),
+            tag
+        )?;
+    }
+    writeln!(f, "</tr>")
+}
+
+/// Escapes HTML special characters in text and attribute values.
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Escapes Markdown table cell: pipes are escaped with backslashes and line
+/// breaks are replaced with `<br>` tags.
+fn markdown_escape(cell: &str) -> String {
+    cell.replace("\r\n", "<br>").replace(['\r', '\n'], "<br>").replace('|', "\\|")
+}
+
+/// Escapes LaTeX special characters. Line breaks are replaced with spaces,
+/// since they are not allowed in `tabular` cells.
+fn latex_escape(cell: &str) -> String {
+    let mut escaped = String::with_capacity(cell.len());
+    for ch in cell.replace("\r\n", " ").chars() {
+        match ch {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(ch);
+            }
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '<' => escaped.push_str("\\textless{}"),
+            '>' => escaped.push_str("\\textgreater{}"),
+            '\r' | '\n' | '\t' => escaped.push(' '),
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Escapes AsciiDoc table cell: pipes are escaped with backslashes and line
+/// breaks are replaced with AsciiDoc hard line breaks.
+fn asciidoc_escape(cell: &str) -> String {
+    cell.replace('|', "\\|").replace("\r\n", "\n").replace(['\r', '\n'], " +\n")
+}
+
+/// Escapes Org-mode table cell: pipes are replaced with `\vert{}` entity and
+/// control characters (including line breaks) are replaced with spaces.
+fn org_escape(cell: &str) -> String {
+    cell.replace("\r\n", " ")
+        .replace('|', "\\vert{}")
+        .chars()
+        .map(|ch| if ch.is_control() { ' ' } else { ch })
+        .collect()
+}
+
+/// Number of terminal columns taken by the cell, with control characters
+/// being escaped.
+fn cell_width(cell: &str) -> usize { display_width(&tsv_escape(cell)) }
+
+fn write_border(
+    f: &mut impl io::Write,
+    widths: &[usize],
+    [left, middle, right]: [char; 3],
+    config: &OutputConfig,
+) -> io::Result<()> {
+    let lines = widths.iter().map(|width| "─".repeat(width + 2)).collect::<Vec<_>>();
+    let border = format!("{}{}{}", left, lines.join(&middle.to_string()), right);
+    writeln!(f, "{}", config.paint(&border, config.theme.accent))
+}
+
+fn write_aligned_row(
+    f: &mut impl io::Write,
+    row: &[String],
+    widths: &[usize],
+    styles: &[Style],
+    config: &OutputConfig,
+) -> io::Result<()> {
+    let borders = config.table_borders;
+    let accent = |separator: &str| config.paint(separator, config.theme.accent);
+    let mut line = String::new();
+    if borders {
+        line.push_str(&accent("│ "));
+    }
+    for (no, (width, style)) in widths.iter().zip(styles).enumerate() {
+        let cell = tsv_escape(row.get(no).map(String::as_str).unwrap_or_default());
+        let cell = config.paint(&cell, *style);
+        let last = no + 1 == widths.len();
+        let padding = if last && !borders { 0 } else { width - display_width(&cell) };
+        line.push_str(&cell);
+        line.push_str(&" ".repeat(padding));
+        if !last {
+            line.push_str(&if borders { accent(" │ ") } else { s!("  ") });
+        }
+    }
+    if borders {
+        line.push_str(&accent(" │"));
+    }
+    writeln!(f, "{}", line)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ColorChoice;
+
+    fn table() -> Table {
+        let mut table = Table::new(vec![s!("name"), s!("memo")]);
+        table.push_row([s!("日本"), s!("x")]);
+        table.push_row([s!("e\u{301}"), s!("👨\u{200D}👩\u{200D}👧")]);
+        table
+    }
+
+    fn render(table: &Table, format: Formatting, config: &OutputConfig) -> String {
+        let mut buf = Vec::new();
+        table.write(format, config, &mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn aligned_wide() {
+        let config = OutputConfig {
+            color: ColorChoice::Never,
+            ..OutputConfig::default()
+        };
+        assert_eq!(
+            render(&table(), Formatting::Table, &config),
+            "name  memo\n日本  x\ne\u{301}     👨\u{200D}👩\u{200D}👧\n"
+        );
+    }
+
+    #[test]
+    fn aligned_wide_borders() {
+        let config = OutputConfig {
+            color: ColorChoice::Never,
+            table_borders: true,
+            ..OutputConfig::default()
+        };
+        assert_eq!(
+            render(&table(), Formatting::Table, &config),
+            "┌──────┬──────┐\n│ name │ memo │\n├──────┼──────┤\n│ 日本 │ x    │\n│ e\u{301}    │ \
+             👨\u{200D}👩\u{200D}👧   │\n└──────┴──────┘\n"
+        );
+    }
+
+    fn special(cells: [&str; 2]) -> Table {
+        let mut table = Table::new(vec![s!("name"), s!("memo")]);
+        table.push_row(cells.map(str::to_owned));
+        table
+    }
+
+    #[test]
+    fn markdown() {
+        let table = special(["a|b", "line\r\nbreak\nand\rmore"]);
+        assert_eq!(
+            render(&table, Formatting::Markdown, &OutputConfig::default()),
+            "| name | memo |\n| --- | --- |\n| a\\|b | line<br>break<br>and<br>more |\n"
+        );
+    }
+
+    #[test]
+    fn html() {
+        let table = special(["<b>&'x'", "\"quoted\"\r\nline"]);
+        let config = OutputConfig {
+            html_table_class: Some(s!("a\"b")),
+            ..OutputConfig::default()
+        };
+        assert_eq!(
+            render(&table, Formatting::Html, &config),
+            "<table class=\"a&quot;b\">\n  <thead>\n    <tr><th>name</th><th>memo</th></tr>\n  \
+             </thead>\n  <tbody>\n    \
+             <tr><td>&lt;b&gt;&amp;&#39;x&#39;</td><td>&quot;quoted&quot;<br>line</td></tr>\n  \
+             </tbody>\n</table>\n"
+        );
+    }
+
+    #[test]
+    fn latex() {
+        let table = special(["&%$#_{}", "\\~^<>\r\nx"]);
+        assert_eq!(
+            render(&table, Formatting::Latex, &OutputConfig::default()),
+            "\\begin{tabular}{ll}\n\\hline\nname & memo \\\\\n\\hline\n\\&\\%\\$\\#\\_\\{\\} & \
+             \\textbackslash{}\\textasciitilde{}\\textasciicircum{}\\textless{}\\textgreater{} x \
+             \\\\\n\\hline\n\\end{tabular}\n"
+        );
+    }
+
+    #[test]
+    fn asciidoc() {
+        let table = special(["a|b", "line\r\nbreak"]);
+        let mut empty = special(["", "x"]);
+        empty.headers[1] = s!("m|emo");
+        assert_eq!(
+            render(&table, Formatting::AsciiDoc, &OutputConfig::default()),
+            "[options=\"header\"]\n|===\n| name | memo\n\n| a\\|b | line +\nbreak\n|===\n"
+        );
+        assert_eq!(
+            render(&empty, Formatting::AsciiDoc, &OutputConfig::default()),
+            "[options=\"header\"]\n|===\n| name | m\\|emo\n\n| | x\n|===\n"
+        );
+    }
+
+    #[test]
+    fn org() {
+        let table = special(["a|b", "日本\r\nx\ty"]);
+        assert_eq!(
+            render(&table, Formatting::Org, &OutputConfig::default()),
+            "| name      | memo     |\n|-----------+----------|\n| a\\vert{}b | 日本 x y |\n"
+        );
+    }
+}
@@ -0,0 +1,445 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Colour themes used for the terminal output.
+
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::str::FromStr;
+use std::{fs, io};
+
+use colored::Color;
+use serde::Deserialize;
+
+/// Errors happening during theme loading
+#[derive(Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum ThemeError {
+    /// unable to read theme file: {0}
+    #[from]
+    Io(io::Error),
+
+    /// invalid theme file: {0}
+    #[from]
+    Toml(toml::de::Error),
+
+    /// unknown theme preset `{0}`
+    UnknownPreset(String),
+
+    /// invalid style `{0}`
+    InvalidStyle(String),
+}
+
+/// Text style made of an optional foreground colour and text attributes.
+///
+/// Styles are parsed from space-separated words, which can be colour names
+/// (like `red` or `bright green`), `#rrggbb` true colours and `bold`,
+/// `dimmed`, `italic` or `underline` attributes. Empty string or `none`
+/// denote plain text.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct Style {
+    /// Foreground colour, if any
+    pub color: Option<Color>,
+
+    /// Bold (increased intensity) text
+    pub bold: bool,
+
+    /// Dimmed (decreased intensity) text
+    pub dimmed: bool,
+
+    /// Italic text
+    pub italic: bool,
+
+    /// Underlined text
+    pub underline: bool,
+}
+
+impl Hash for Style {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Colour escape codes depend on the terminal capabilities, so the debug
+        // representation is hashed instead
+        self.color.map(|color| format!("{:?}", color)).hash(state);
+        self.bold.hash(state);
+        self.dimmed.hash(state);
+        self.italic.hash(state);
+        self.underline.hash(state);
+    }
+}
+
+impl Style {
+    /// Plain text style
+    pub const fn plain() -> Style {
+        Style {
+            color: None,
+            bold: false,
+            dimmed: false,
+            italic: false,
+            underline: false,
+        }
+    }
+
+    /// Style with the foreground colour only
+    pub const fn color(color: Color) -> Style {
+        Style {
+            color: Some(color),
+            ..Style::plain()
+        }
+    }
+
+    /// Adds bold attribute to the style
+    pub const fn bold(self) -> Style { Style { bold: true, ..self } }
+
+    /// Adds dimmed attribute to the style
+    pub const fn dimmed(self) -> Style {
+        Style {
+            dimmed: true,
+            ..self
+        }
+    }
+
+    /// Paints the text with the style if `enabled`. Escape sequences are
+    /// produced directly, since `colored` crate decides on its own whether to
+    /// use the colours, looking at the standard output only.
+    pub fn paint(&self, text: &str, enabled: bool) -> String {
+        let mut codes = Vec::new();
+        if self.bold {
+            codes.push("1".into());
+        }
+        if self.dimmed {
+            codes.push("2".into());
+        }
+        if self.italic {
+            codes.push("3".into());
+        }
+        if self.underline {
+            codes.push("4".into());
+        }
+        if let Some(color) = self.color {
+            codes.push(color.to_fg_str());
+        }
+        if !enabled || codes.is_empty() || text.is_empty() {
+            return text.to_owned();
+        }
+        format!("\x1B[{}m{}\x1B[0m", codes.join(";"), text)
+    }
+}
+
+impl FromStr for Style {
+    type Err = ThemeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut style = Style::plain();
+        let mut words = s.split_whitespace().map(str::to_lowercase);
+        while let Some(word) = words.next() {
+            match word.as_str() {
+                "none" => {}
+                "bold" => style.bold = true,
+                "dimmed" | "dim" => style.dimmed = true,
+                "italic" => style.italic = true,
+                "underline" => style.underline = true,
+                "bright" => {
+                    let name = format!("bright {}", words.next().unwrap_or_default());
+                    style.color = Some(parse_color(&name, s)?);
+                }
+                name => style.color = Some(parse_color(name, s)?),
+            }
+        }
+        Ok(style)
+    }
+}
+
+fn parse_color(name: &str, style: &str) -> Result<Color, ThemeError> {
+    let invalid = || ThemeError::InvalidStyle(style.to_owned());
+    match name.strip_prefix('#') {
+        Some(hex) if hex.len() == 6 => {
+            let rgb = u32::from_str_radix(hex, 16).map_err(|_| invalid())?;
+            Ok(Color::TrueColor {
+                r: (rgb >> 16) as u8,
+                g: (rgb >> 8) as u8,
+                b: rgb as u8,
+            })
+        }
+        Some(_) => Err(invalid()),
+        None => name.parse().map_err(|_| invalid()),
+    }
+}
+
+/// Colour theme of the terminal output
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Theme {
+    /// Style of column headers
+    pub header: Style,
+
+    /// Style of map keys identifying the items
+    pub id: Style,
+
+    /// Style of the message reporting that there are no items to output
+    pub empty: Style,
+
+    /// Style of error messages
+    pub error: Style,
+
+    /// Style of the table borders and document markers
+    pub accent: Style,
+
+    /// Style of object keys in highlighted JSON and YAML output
+    pub key: Style,
+
+    /// Style of strings in highlighted JSON and YAML output
+    pub string: Style,
+
+    /// Style of numbers in highlighted JSON and YAML output
+    pub number: Style,
+
+    /// Style of boolean and null literals in highlighted JSON and YAML output
+    pub literal: Style,
+
+    /// Styles of the data cells in [`crate::Formatting::Table`] output, by the
+    /// column header
+    pub columns: BTreeMap<String, Style>,
+}
+
+impl Default for Theme {
+    fn default() -> Self { Theme::dark() }
+}
+
+impl Theme {
+    /// Theme for terminals with dark background
+    pub fn dark() -> Theme {
+        Theme {
+            header: Style::color(Color::BrightGreen),
+            id: Style::color(Color::BrightWhite),
+            empty: Style::color(Color::Red),
+            error: Style::color(Color::Red),
+            accent: Style::color(Color::BrightBlack),
+            key: Style::color(Color::BrightBlue).bold(),
+            string: Style::color(Color::Green),
+            number: Style::color(Color::Cyan),
+            literal: Style::color(Color::Yellow),
+            columns: BTreeMap::new(),
+        }
+    }
+
+    /// Theme for terminals with light background
+    pub fn light() -> Theme {
+        Theme {
+            header: Style::color(Color::Blue).bold(),
+            id: Style::color(Color::Black).bold(),
+            empty: Style::color(Color::Red),
+            error: Style::color(Color::Red),
+            accent: Style::color(Color::BrightBlack),
+            key: Style::color(Color::Blue).bold(),
+            string: Style::color(Color::Green),
+            number: Style::color(Color::Magenta),
+            literal: Style::color(Color::Red),
+            columns: BTreeMap::new(),
+        }
+    }
+
+    /// Theme using text attributes only, without colours
+    pub fn monochrome() -> Theme {
+        Theme {
+            header: Style::plain().bold(),
+            id: Style::plain().bold(),
+            empty: Style::plain().bold(),
+            error: Style::plain().bold(),
+            accent: Style::plain().dimmed(),
+            key: Style::plain().bold(),
+            string: Style::plain(),
+            number: Style::plain(),
+            literal: Style::plain().dimmed(),
+            columns: BTreeMap::new(),
+        }
+    }
+
+    /// Loads theme from TOML file, see [`Theme::from_toml`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Theme, ThemeError> {
+        Theme::from_toml(&fs::read_to_string(path)?)
+    }
+
+    /// Parses theme from TOML document. All the keys are optional; styles
+    /// which are not given are taken from the `preset` theme, which is
+    /// [`Theme::dark`] by default:
+    ///
+    /// ```toml
+    /// preset = "light"
+    /// header = "bold blue"
+    /// id = "bright white"
+    ///
+    /// [columns]
+    /// Amount = "yellow"
+    /// ```
+    pub fn from_toml(s: &str) -> Result<Theme, ThemeError> {
+        let file: ThemeFile = toml::from_str(s)?;
+        let mut theme = match file.preset {
+            Some(preset) => preset.parse()?,
+            None => Theme::default(),
+        };
+        let styles = [
+            (file.header, &mut theme.header),
+            (file.id, &mut theme.id),
+            (file.empty, &mut theme.empty),
+            (file.error, &mut theme.error),
+            (file.accent, &mut theme.accent),
+            (file.key, &mut theme.key),
+            (file.string, &mut theme.string),
+            (file.number, &mut theme.number),
+            (file.literal, &mut theme.literal),
+        ];
+        for (spec, style) in styles {
+            if let Some(spec) = spec {
+                *style = spec.parse()?;
+            }
+        }
+        for (column, spec) in file.columns {
+            theme.columns.insert(column, spec.parse()?);
+        }
+        Ok(theme)
+    }
+
+    /// Returns style of the data cells in the column with the given header.
+    pub(crate) fn column(&self, header: &str) -> Style {
+        self.columns.get(header).copied().unwrap_or_default()
+    }
+}
+
+impl FromStr for Theme {
+    type Err = ThemeError;
+
+    /// Parses name of one of the theme presets.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "dark" => Ok(Theme::dark()),
+            "light" => Ok(Theme::light()),
+            "monochrome" | "mono" => Ok(Theme::monochrome()),
+            _ => Err(ThemeError::UnknownPreset(s.to_owned())),
+        }
+    }
+}
+
+/// Contents of the theme file
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    preset: Option<String>,
+    header: Option<String>,
+    id: Option<String>,
+    empty: Option<String>,
+    error: Option<String>,
+    accent: Option<String>,
+    key: Option<String>,
+    string: Option<String>,
+    number: Option<String>,
+    literal: Option<String>,
+    #[serde(default)]
+    columns: BTreeMap<String, String>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn style() {
+        assert_eq!("".parse::<Style>().unwrap(), Style::plain());
+        assert_eq!("none".parse::<Style>().unwrap(), Style::plain());
+        assert_eq!("red".parse::<Style>().unwrap(), Style::color(Color::Red));
+        assert_eq!("Bold  RED".parse::<Style>().unwrap(), Style::color(Color::Red).bold());
+        assert_eq!("bright green".parse::<Style>().unwrap(), Style::color(Color::BrightGreen));
+        assert_eq!("dim bright black".parse::<Style>().unwrap(), Style {
+            color: Some(Color::BrightBlack),
+            dimmed: true,
+            ..Style::plain()
+        });
+        assert_eq!("italic underline #FF8000".parse::<Style>().unwrap(), Style {
+            color: Some(Color::TrueColor {
+                r: 0xff,
+                g: 0x80,
+                b: 0x00
+            }),
+            italic: true,
+            underline: true,
+            ..Style::plain()
+        });
+    }
+
+    #[test]
+    fn invalid_style() {
+        for spec in ["sparkly", "bold sparkly", "bright", "bright sparkly", "#ff80", "#gggggg", "#"]
+        {
+            let err = spec.parse::<Style>().unwrap_err();
+            assert!(matches!(err, ThemeError::InvalidStyle(ref s) if s == spec), "{}", spec);
+        }
+        assert_eq!(
+            "bold sparkly".parse::<Style>().unwrap_err().to_string(),
+            "invalid style `bold sparkly`"
+        );
+    }
+
+    #[test]
+    fn from_toml() {
+        assert_eq!(Theme::from_toml("").unwrap(), Theme::dark());
+        let theme = Theme::from_toml(
+            r##"
+            preset = "light"
+            header = "bold #00ff00"
+            number = "none"
+
+            [columns]
+            Amount = "bright yellow"
+            "##,
+        )
+        .unwrap();
+        assert_eq!(theme, Theme {
+            header: Style::color(Color::TrueColor {
+                r: 0,
+                g: 0xff,
+                b: 0
+            })
+            .bold(),
+            number: Style::plain(),
+            columns: BTreeMap::from([(s!("Amount"), Style::color(Color::BrightYellow))]),
+            ..Theme::light()
+        });
+        assert_eq!(theme.column("Amount"), Style::color(Color::BrightYellow));
+        assert_eq!(theme.column("Fee"), Style::plain());
+        assert_eq!(Theme::from_toml("preset = 'mono'").unwrap(), Theme::monochrome());
+    }
+
+    #[test]
+    fn invalid_toml() {
+        let err = Theme::from_toml("header = 'bold'\nheadr = 'red'").unwrap_err();
+        assert!(matches!(err, ThemeError::Toml(_)));
+        assert!(err.to_string().contains("unknown field `headr`"), "{}", err);
+        let err = Theme::from_toml("[columns]\nAmount = 'sparkly'").unwrap_err();
+        assert!(matches!(err, ThemeError::InvalidStyle(ref s) if s == "sparkly"));
+        let err = Theme::from_toml("preset = 'solarized'").unwrap_err();
+        assert!(matches!(err, ThemeError::UnknownPreset(ref s) if s == "solarized"));
+        assert!(matches!(Theme::from_toml("header = 1").unwrap_err(), ThemeError::Toml(_)));
+    }
+
+    #[test]
+    fn load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("theme.toml");
+        assert!(matches!(Theme::load(&path).unwrap_err(), ThemeError::Io(_)));
+        fs::write(&path, "preset = 'monochrome'\nid = 'underline'\n").unwrap();
+        assert_eq!(Theme::load(&path).unwrap(), Theme {
+            id: Style {
+                underline: true,
+                ..Style::plain()
+            },
+            ..Theme::monochrome()
+        });
+    }
+}
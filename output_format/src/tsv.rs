@@ -0,0 +1,71 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Tab-separated output using the escaping of PostgreSQL `COPY` text format.
+
+use std::borrow::Cow;
+
+/// Escapes a single tab-separated field: backslashes, tabs, line feeds and
+/// carriage returns are replaced with `\\`, `\t`, `\n` and `\r` escape
+/// sequences, such that each record always occupies a single line and has a
+/// fixed number of columns.
+pub fn tsv_escape(field: &str) -> Cow<'_, str> {
+    if !field.contains(['\\', '\t', '\n', '\r']) {
+        return Cow::Borrowed(field);
+    }
+    let mut escaped = String::with_capacity(field.len() + 2);
+    for ch in field.chars() {
+        match ch {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            ch => escaped.push(ch),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+/// Composes a single tab-separated line (without line ending) out of
+/// escaped `fields`.
+pub(crate) fn tsv_line(fields: impl IntoIterator<Item = impl AsRef<str>>) -> String {
+    fields
+        .into_iter()
+        .map(|field| tsv_escape(field.as_ref()).into_owned())
+        .collect::<Vec<_>>()
+        .join("\t")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn escape_plain() {
+        assert_eq!(tsv_escape("plain, \"value\""), "plain, \"value\"");
+        assert!(matches!(tsv_escape("plain"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn escape_special() {
+        assert_eq!(tsv_escape("back\\slash"), "back\\\\slash");
+        assert_eq!(tsv_escape("a\tb"), "a\\tb");
+        assert_eq!(tsv_escape("a\nb"), "a\\nb");
+        assert_eq!(tsv_escape("a\rb"), "a\\rb");
+        assert_eq!(tsv_escape("\\t\t\r\n"), "\\\\t\\t\\r\\n");
+    }
+
+    #[test]
+    fn line() {
+        assert_eq!(tsv_line(["a\tb", "c", ""]), "a\\tb\tc\t");
+    }
+}
@@ -0,0 +1,180 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Order-preserving representation of serialized data.
+//!
+//! Unlike `serde_json::Value`, objects keep their fields in the order they
+//! were produced by the serializer, independently of `serde_json` features
+//! enabled elsewhere in the dependency graph.
+
+use std::fmt;
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+use serde_json::Number;
+
+use crate::OutputError;
+
+/// Serialized data in JSON data model with objects represented as ordered
+/// lists of fields.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Serializes data into JSON and reads it back, keeping the field order.
+    pub fn from_serialize(data: &(impl Serialize + ?Sized)) -> Result<Value, serde_json::Error> {
+        serde_json::from_slice(&serde_json::to_vec(data)?)
+    }
+
+    /// Composes fields of a map entry: the map `key` under `key_name`
+    /// followed by the fields of the `record`. If the record is not an object
+    /// or has a field named as the key, it is nested under `value` field
+    /// instead, such that none of its data are lost.
+    pub fn keyed_fields(
+        key_name: &str,
+        key: Value,
+        record: Value,
+    ) -> Result<Vec<(String, Value)>, OutputError> {
+        let mut fields = vec![(key_name.to_owned(), key)];
+        match record {
+            Value::Object(record) if record.iter().all(|(name, _)| name != key_name) => {
+                fields.extend(record)
+            }
+            _ if key_name == "value" => return Err(OutputError::KeyCollision(s!("value"))),
+            record => fields.push((s!("value"), record)),
+        }
+        Ok(fields)
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Null => serializer.serialize_unit(),
+            Value::Bool(val) => serializer.serialize_bool(*val),
+            Value::Number(val) => val.serialize(serializer),
+            Value::String(val) => serializer.serialize_str(val),
+            Value::Array(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(item)?;
+                }
+                seq.end()
+            }
+            Value::Object(fields) => {
+                let mut map = serializer.serialize_map(Some(fields.len()))?;
+                for (name, item) in fields {
+                    map.serialize_entry(name, item)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result { f.write_str("any JSON value") }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Value, E> { Ok(Value::Bool(v)) }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Value, E> { Ok(Value::Number(v.into())) }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Value, E> { Ok(Value::Number(v.into())) }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Value, E> {
+        Ok(Number::from_f64(v).map(Value::Number).unwrap_or(Value::Null))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::String(v.to_owned()))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Value, E> { Ok(Value::String(v)) }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Value, E> { Ok(Value::Null) }
+
+    fn visit_none<E: de::Error>(self) -> Result<Value, E> { Ok(Value::Null) }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        Value::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut items = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut fields = Vec::<(String, Value)>::with_capacity(map.size_hint().unwrap_or_default());
+        while let Some((name, item)) = map.next_entry::<String, Value>()? {
+            // Duplicate fields replace the earlier ones, like `serde_json` does
+            match fields.iter_mut().find(|(existing, _)| *existing == name) {
+                Some((_, existing)) => *existing = item,
+                None => fields.push((name, item)),
+            }
+        }
+        Ok(Value::Object(fields))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::Serialize;
+
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Record {
+        zulu: u8,
+        alpha: i8,
+        mike: Vec<f32>,
+    }
+
+    #[test]
+    fn field_order() {
+        let record = Record {
+            zulu: 1,
+            alpha: -1,
+            mike: vec![0.5],
+        };
+        let value = Value::from_serialize(&record).unwrap();
+        assert_eq!(
+            value,
+            Value::Object(vec![
+                (s!("zulu"), Value::Number(1u64.into())),
+                (s!("alpha"), Value::Number((-1i64).into())),
+                (s!("mike"), Value::Array(vec![Value::Number(Number::from_f64(0.5).unwrap())])),
+            ])
+        );
+        assert_eq!(serde_json::to_string(&value).unwrap(), serde_json::to_string(&record).unwrap());
+    }
+}
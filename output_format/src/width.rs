@@ -0,0 +1,118 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Computation of the string width as it is displayed by a terminal.
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Computes number of terminal columns taken by the string.
+///
+/// The width is computed per extended grapheme cluster, such that combining
+/// marks take no space, while East Asian wide characters and emoji sequences
+/// take two columns. ANSI escape sequences (like the ones produced by the
+/// `colored` crate) are excluded from the computation.
+pub fn display_width(s: &str) -> usize {
+    strip_ansi(s).graphemes(true).map(|grapheme| grapheme.width().min(2)).sum()
+}
+
+/// Removes ANSI CSI (`ESC [ ... <final>`) and OSC (`ESC ] ... BEL`)
+/// escape sequences from the string.
+pub(crate) fn strip_ansi(s: &str) -> String {
+    let mut stripped = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch != '\x1B' {
+            stripped.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('[') => {
+                for ch in chars.by_ref() {
+                    if ('\x40'..='\x7E').contains(&ch) {
+                        break;
+                    }
+                }
+            }
+            Some(']') => {
+                while let Some(ch) = chars.next() {
+                    if ch == '\x07' {
+                        break;
+                    }
+                    if ch == '\x1B' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // Two-character escape sequences
+            _ => {}
+        }
+    }
+    stripped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn width_ascii() {
+        assert_eq!(display_width(""), 0);
+        assert_eq!(display_width("hello"), 5);
+    }
+
+    #[test]
+    fn width_cjk() {
+        assert_eq!(display_width("日本語"), 6);
+        assert_eq!(display_width("a日b"), 4);
+    }
+
+    #[test]
+    fn width_combining() {
+        assert_eq!(display_width("e\u{301}"), 1);
+        assert_eq!(display_width("cafe\u{301}"), 4);
+    }
+
+    #[test]
+    fn width_emoji() {
+        assert_eq!(display_width("👍"), 2);
+        // Man, woman and girl joined with ZWJ into a single family emoji
+        assert_eq!(display_width("👨\u{200D}👩\u{200D}👧"), 2);
+        // Thumbs up with skin tone modifier
+        assert_eq!(display_width("👍\u{1F3FD}"), 2);
+    }
+
+    #[test]
+    fn strip_csi() {
+        assert_eq!(strip_ansi("\x1B[1;31m日本\x1B[0m"), "日本");
+        assert_eq!(display_width("\x1B[1;31m日本\x1B[0m"), 4);
+        assert_eq!(strip_ansi("a\x1B[38;5;208mb\x1B[mc"), "abc");
+    }
+
+    #[test]
+    fn strip_osc() {
+        let bel = "\x1B]8;;https://example.com\x07link\x1B]8;;\x07";
+        assert_eq!(strip_ansi(bel), "link");
+        assert_eq!(display_width(bel), 4);
+        let st = "\x1B]8;;https://example.com\x1B\\日本\x1B]8;;\x1B\\";
+        assert_eq!(strip_ansi(st), "日本");
+        assert_eq!(display_width(st), 4);
+    }
+
+    #[test]
+    fn strip_nested() {
+        let s = "\x1B]8;;https://example.com\x07\x1B[1m👍\x1B[0m\x1B]8;;\x07";
+        assert_eq!(strip_ansi(s), "👍");
+        assert_eq!(display_width(s), 2);
+    }
+}
@@ -0,0 +1,129 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Excel workbook (XLSX) output.
+
+use std::collections::HashSet;
+use std::io;
+
+use rust_xlsxwriter::{Format, Workbook};
+
+use crate::table::Table;
+use crate::OutputError;
+
+/// Maximal length of a worksheet name allowed by Excel.
+const SHEET_NAME_MAX_LEN: usize = 31;
+
+/// Writes tables into a workbook with a worksheet per table. All cells are
+/// written as text, such that spreadsheet applications do not strip leading
+/// zeros or round long numbers; the header row is frozen.
+pub(crate) fn write_xlsx<'a>(
+    f: &mut impl io::Write,
+    sheets: impl IntoIterator<Item = (String, &'a Table)>,
+) -> Result<(), OutputError> {
+    let text = Format::new().set_num_format("@");
+    let header = Format::new().set_num_format("@").set_bold();
+
+    let mut workbook = Workbook::new();
+    let mut used = HashSet::new();
+    for (name, table) in sheets {
+        let sheet = workbook.add_worksheet();
+        sheet.set_name(sheet_name(&name, &mut used))?;
+        for (col, cell) in table.headers().iter().enumerate() {
+            sheet.write_string_with_format(0, col as u16, cell, &header)?;
+        }
+        for (row, cells) in table.rows().iter().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                sheet.write_string_with_format(row as u32 + 1, col as u16, cell, &text)?;
+            }
+        }
+        sheet.set_freeze_panes(1, 0)?;
+        sheet.autofit();
+    }
+
+    f.write_all(&workbook.save_to_buffer()?)?;
+    Ok(())
+}
+
+/// Converts arbitrary string into a valid worksheet name, which is unique
+/// (case-insensitively) among the names used in the workbook so far.
+fn sheet_name(name: &str, used: &mut HashSet<String>) -> String {
+    let name = name
+        .chars()
+        .map(|ch| if matches!(ch, '[' | ']' | ':' | '*' | '?' | '/' | '\\') { '_' } else { ch })
+        .collect::<String>();
+    let name = name.trim_matches('\'');
+    let name = if name.is_empty() || name.eq_ignore_ascii_case("history") {
+        format!("{}_", name)
+    } else {
+        name.to_owned()
+    };
+
+    let mut unique = truncate(&name, SHEET_NAME_MAX_LEN);
+    let mut no = 1;
+    while used.contains(&unique.to_lowercase()) {
+        no += 1;
+        let suffix = format!(" ({})", no);
+        unique = truncate(&name, SHEET_NAME_MAX_LEN - suffix.len()) + &suffix;
+    }
+    used.insert(unique.to_lowercase());
+    unique
+}
+
+/// Truncates the name to the given number of characters, ensuring it does
+/// not end with an apostrophe, which is not allowed by Excel.
+fn truncate(name: &str, len: usize) -> String {
+    name.chars().take(len).collect::<String>().trim_end_matches('\'').to_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn invalid_chars() {
+        let mut used = HashSet::new();
+        assert_eq!(sheet_name(r"a[b]:c*d?e/f\g", &mut used), "a_b__c_d_e_f_g");
+        assert_eq!(sheet_name("'quoted'", &mut used), "quoted");
+        assert_eq!(sheet_name("it's", &mut used), "it's");
+    }
+
+    #[test]
+    fn reserved() {
+        let mut used = HashSet::new();
+        assert_eq!(sheet_name("History", &mut used), "History_");
+        assert_eq!(sheet_name("history", &mut used), "history_ (2)");
+        assert_eq!(sheet_name("", &mut used), "_");
+        assert_eq!(sheet_name("''", &mut used), "_ (2)");
+    }
+
+    #[test]
+    fn truncation() {
+        let mut used = HashSet::new();
+        let long = "abcdefghij".repeat(4);
+        assert_eq!(sheet_name(&long, &mut used), &long[..31]);
+        assert_eq!(sheet_name(&long, &mut used), format!("{} (2)", &long[..27]));
+        assert_eq!(sheet_name(&long, &mut used), format!("{} (3)", &long[..27]));
+        let quote = format!("{}'tail", "a".repeat(30));
+        assert_eq!(sheet_name(&quote, &mut used), "a".repeat(30));
+    }
+
+    #[test]
+    fn uniqueness() {
+        let mut used = HashSet::new();
+        assert_eq!(sheet_name("Sheet", &mut used), "Sheet");
+        assert_eq!(sheet_name("sheet", &mut used), "sheet (2)");
+        assert_eq!(sheet_name("SHEET", &mut used), "SHEET (3)");
+        assert_eq!(sheet_name("a/b", &mut used), "a_b");
+        assert_eq!(sheet_name("a:b", &mut used), "a_b (2)");
+    }
+}
@@ -0,0 +1,203 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! XML output built on top of serde data model.
+
+use std::io;
+
+use serde::Serialize;
+
+use crate::value::Value;
+use crate::{OutputConfig, OutputError};
+
+/// Representation of map keys in XML output
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Default, Display)]
+pub enum XmlKey {
+    /// Map key is put into an attribute of the item element
+    #[default]
+    #[display("attribute")]
+    Attribute,
+
+    /// Map key is put into the first child element of the item element
+    #[display("element")]
+    Element,
+}
+
+const XML_DECLARATION: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n";
+
+/// Writes a single record as an XML document with the item element as the
+/// document root.
+pub(crate) fn write_xml_record(
+    f: &mut impl io::Write,
+    data: &(impl Serialize + ?Sized),
+    config: &OutputConfig,
+) -> Result<(), OutputError> {
+    let value = Value::from_serialize(data).map_err(OutputError::Xml)?;
+    let mut out = XML_DECLARATION.to_owned();
+    write_element(&mut out, 0, &config.xml_item, None, &value);
+    f.write_all(out.as_bytes())?;
+    Ok(())
+}
+
+/// Writes collection items as an XML document with the root element
+/// containing an item element per each of the items. If the items have keys
+/// (i.e. they come from a map), the keys are put into the item elements
+/// according to [`OutputConfig::xml_key`].
+pub(crate) fn write_xml_list<'a, T>(
+    f: &mut impl io::Write,
+    items: impl IntoIterator<Item = (Option<String>, &'a T)>,
+    config: &OutputConfig,
+) -> Result<(), OutputError>
+where
+    T: Serialize + 'a,
+{
+    let mut out = XML_DECLARATION.to_owned();
+    let mut items = items.into_iter().peekable();
+    if items.peek().is_none() {
+        write_element(&mut out, 0, &config.xml_root, None, &Value::Null);
+        f.write_all(out.as_bytes())?;
+        return Ok(());
+    }
+
+    out.push_str(&format!("<{}>\n", xml_name(&config.xml_root)));
+    for (key, item) in items {
+        let value = Value::from_serialize(item).map_err(OutputError::Xml)?;
+        match (key, config.xml_key) {
+            (None, _) => write_element(&mut out, 1, &config.xml_item, None, &value),
+            (Some(key), XmlKey::Attribute) => write_element(
+                &mut out,
+                1,
+                &config.xml_item,
+                Some((&config.xml_key_name, &key)),
+                &value,
+            ),
+            (Some(key), XmlKey::Element) => {
+                let fields = Value::keyed_fields(&config.xml_key_name, Value::String(key), value)?;
+                write_element(&mut out, 1, &config.xml_item, None, &Value::Object(fields))
+            }
+        }
+    }
+    out.push_str(&format!("</{}>\n", xml_name(&config.xml_root)));
+
+    f.write_all(out.as_bytes())?;
+    Ok(())
+}
+
+fn write_element(
+    out: &mut String,
+    depth: usize,
+    name: &str,
+    attr: Option<(&str, &str)>,
+    value: &Value,
+) {
+    let name = xml_name(name);
+    out.push_str(&"  ".repeat(depth));
+    out.push('<');
+    out.push_str(&name);
+    if let Some((attr_name, attr_value)) = attr {
+        out.push_str(&format!(" {}=\"{}\"", xml_name(attr_name), xml_escape(attr_value)));
+    }
+    let children = match value {
+        Value::Null => None,
+        Value::Bool(val) => return write_text(out, &name, &val.to_string()),
+        Value::Number(val) => return write_text(out, &name, &val.to_string()),
+        Value::String(val) => return write_text(out, &name, val),
+        Value::Array(items) if items.is_empty() => None,
+        Value::Array(items) => Some(items.iter().map(|item| ("value", item)).collect::<Vec<_>>()),
+        Value::Object(fields) if fields.is_empty() => None,
+        Value::Object(fields) => {
+            Some(fields.iter().map(|(name, item)| (name.as_str(), item)).collect())
+        }
+    };
+    match children {
+        None => out.push_str("/>\n"),
+        Some(children) => {
+            out.push_str(">\n");
+            for (child, item) in children {
+                write_field(out, depth + 1, child, item);
+            }
+            out.push_str(&"  ".repeat(depth));
+            out.push_str(&format!("</{}>\n", name));
+        }
+    }
+}
+
+/// Writes structure field; fields containing sequences are represented as
+/// repeated elements with the field name.
+fn write_field(out: &mut String, depth: usize, name: &str, value: &Value) {
+    match value {
+        Value::Array(items) => items.iter().for_each(|item| write_field(out, depth, name, item)),
+        value => write_element(out, depth, name, None, value),
+    }
+}
+
+fn write_text(out: &mut String, name: &str, text: &str) {
+    out.push_str(&format!(">{}</{}>\n", xml_escape(text), name));
+}
+
+/// Escapes text for use in XML element content and attribute values.
+/// Characters which are not allowed in XML 1.0 documents are replaced with
+/// U+FFFD replacement character.
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Preserve carriage returns from line-ending normalization
+            '\r' => escaped.push_str("&#13;"),
+            '\t' | '\n' => escaped.push(ch),
+            ch if ch < ' ' || ch == '\u{FFFE}' || ch == '\u{FFFF}' => escaped.push('\u{FFFD}'),
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Converts arbitrary string into a valid XML element or attribute name.
+fn xml_name(name: &str) -> String {
+    let mut valid = name
+        .chars()
+        .map(|ch| if ch.is_alphanumeric() || matches!(ch, '_' | '-' | '.') { ch } else { '_' })
+        .collect::<String>();
+    if !valid.starts_with(|ch: char| ch.is_alphabetic() || ch == '_') {
+        valid.insert(0, '_');
+    }
+    valid
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn escape() {
+        assert_eq!(xml_escape("plain über"), "plain über");
+        assert_eq!(xml_escape("&<>\"'"), "&amp;&lt;&gt;&quot;&apos;");
+        assert_eq!(xml_escape("a\tb\nc\rd"), "a\tb\nc&#13;d");
+        assert_eq!(xml_escape("\u{0}\u{1B}\u{FFFE}\u{FFFF}"), "\u{FFFD}\u{FFFD}\u{FFFD}\u{FFFD}");
+    }
+
+    #[test]
+    fn name() {
+        assert_eq!(xml_name("txid"), "txid");
+        assert_eq!(xml_name("_private.v-2"), "_private.v-2");
+        assert_eq!(xml_name("über"), "über");
+        assert_eq!(xml_name("a b:c<d>"), "a_b_c_d_");
+        assert_eq!(xml_name("1st"), "_1st");
+        assert_eq!(xml_name("-x"), "_-x");
+        assert_eq!(xml_name(""), "_");
+    }
+}
@@ -0,0 +1,241 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Arrow record batches built from the tabular representation of records.
+
+#![cfg(feature = "arrow")]
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float64Type, Int64Type, UInt64Type};
+use arrow_array::Array;
+use arrow_schema::DataType;
+use output_format::{to_record_batch, ColumnType, OutputCompact, OutputError, OutputFormat};
+use serde::Serialize;
+
+#[derive(Serialize, OutputFormat, OutputCompact)]
+#[output(compact = "{txid}:{vout}")]
+struct Utxo {
+    txid: String,
+    vout: u32,
+    #[output(header = "Memo")]
+    memo: String,
+    #[output(skip)]
+    secret: String,
+}
+
+#[derive(Serialize)]
+struct Payment {
+    amount: u64,
+    fee: u64,
+    label: String,
+}
+
+impl OutputCompact for Payment {
+    fn output_compact(&self) -> String { self.label.clone() }
+}
+
+impl OutputFormat for Payment {
+    fn output_headers() -> Vec<String> { vec![s("Label"), s("Fee"), s("Amount")] }
+
+    fn output_id_string(&self) -> String { self.label.clone() }
+
+    fn output_fields(&self) -> Vec<String> {
+        vec![self.label.clone(), self.fee.to_string(), self.amount.to_string()]
+    }
+
+    fn output_column_types() -> Vec<ColumnType> {
+        vec![ColumnType::String, ColumnType::UInt64, ColumnType::UInt64]
+    }
+}
+
+#[derive(Serialize, OutputFormat, OutputCompact)]
+#[output(compact = "{height}")]
+struct Block {
+    height: u64,
+    delta: i32,
+    mined: bool,
+    difficulty: f64,
+    fees: Option<u32>,
+    #[output(with = "hex")]
+    nonce: u32,
+}
+
+fn hex(n: &u32) -> String { format!("{:08x}", n) }
+
+fn s(s: &str) -> String { s.to_owned() }
+
+fn column_names(batch: &arrow_array::RecordBatch) -> Vec<String> {
+    batch.schema().fields().iter().map(|field| field.name().clone()).collect()
+}
+
+#[test]
+fn skipped_fields() {
+    let utxos = vec![
+        Utxo {
+            txid: s("ab"),
+            vout: 0,
+            memo: s("first"),
+            secret: s("hidden"),
+        },
+        Utxo {
+            txid: s("cd"),
+            vout: 3,
+            memo: s("second"),
+            secret: s("hidden"),
+        },
+    ];
+    let batch = to_record_batch(&utxos).unwrap();
+    assert_eq!(column_names(&batch), vec!["txid", "vout", "Memo"]);
+    let schema = batch.schema();
+    assert_eq!(schema.field(0).data_type(), &DataType::Utf8);
+    assert_eq!(schema.field(1).data_type(), &DataType::UInt64);
+    assert_eq!(schema.field(2).data_type(), &DataType::Utf8);
+    assert_eq!(batch.column(2).as_string::<i32>().value(1), "second");
+}
+
+#[test]
+fn reordered_headers() {
+    let payments = vec![
+        Payment {
+            amount: 1000,
+            fee: 10,
+            label: s("rent"),
+        },
+        Payment {
+            amount: 2000,
+            fee: 10,
+            label: s("food"),
+        },
+    ];
+    let batch = to_record_batch(&payments).unwrap();
+    assert_eq!(column_names(&batch), vec!["Label", "Fee", "Amount"]);
+    assert_eq!(batch.column(0).as_string::<i32>().value(1), "food");
+    let fee = batch.column(1).as_primitive::<UInt64Type>();
+    assert_eq!(fee.values(), &[10, 10]);
+    let amount = batch.column(2).as_primitive::<UInt64Type>();
+    assert_eq!(amount.values(), &[1000, 2000]);
+}
+
+#[test]
+fn untyped_columns() {
+    #[derive(Serialize)]
+    struct Balance {
+        sats: u64,
+    }
+    impl OutputCompact for Balance {
+        fn output_compact(&self) -> String { self.sats.to_string() }
+    }
+    impl OutputFormat for Balance {
+        fn output_headers() -> Vec<String> { vec![s("BTC")] }
+        fn output_id_string(&self) -> String { self.output_compact() }
+        fn output_fields(&self) -> Vec<String> { vec![format!("{:.8}", self.sats as f64 / 1e8)] }
+    }
+
+    let batch =
+        to_record_batch(&[Balance { sats: 150_000_000 }, Balance { sats: u64::MAX }]).unwrap();
+    assert_eq!(column_names(&batch), vec!["BTC"]);
+    assert_eq!(batch.column(0).data_type(), &DataType::Utf8);
+    assert_eq!(batch.column(0).as_string::<i32>().value(0), "1.50000000");
+}
+
+#[test]
+fn typed_columns() {
+    let blocks = vec![
+        Block {
+            height: u64::MAX,
+            delta: -1,
+            mined: true,
+            difficulty: 0.5,
+            fees: None,
+            nonce: 255,
+        },
+        Block {
+            height: 5,
+            delta: 2,
+            mined: false,
+            difficulty: 1e10,
+            fees: Some(7),
+            nonce: 0,
+        },
+    ];
+    let batch = to_record_batch(&blocks).unwrap();
+    let height = batch.column(0).as_primitive::<UInt64Type>();
+    assert_eq!(height.values(), &[u64::MAX, 5]);
+    let delta = batch.column(1).as_primitive::<Int64Type>();
+    assert_eq!(delta.values(), &[-1, 2]);
+    let mined = batch.column(2).as_boolean();
+    assert!(mined.value(0) && !mined.value(1));
+    let difficulty = batch.column(3).as_primitive::<Float64Type>();
+    assert_eq!(difficulty.values(), &[0.5, 1e10]);
+    let fees = batch.column(4).as_primitive::<UInt64Type>();
+    assert!(fees.is_null(0));
+    assert_eq!(fees.value(1), 7);
+    assert_eq!(batch.column(5).as_string::<i32>().value(0), "000000ff");
+}
+
+#[test]
+fn schema_independent_of_data() {
+    let block = |height| Block {
+        height,
+        delta: 0,
+        mined: false,
+        difficulty: 0.0,
+        fees: None,
+        nonce: 0,
+    };
+    let empty = to_record_batch::<Block>(&[]).unwrap();
+    assert_eq!(empty.num_rows(), 0);
+    let types =
+        empty.schema().fields().iter().map(|field| field.data_type().clone()).collect::<Vec<_>>();
+    assert_eq!(types, [
+        DataType::UInt64,
+        DataType::Int64,
+        DataType::Boolean,
+        DataType::Float64,
+        DataType::UInt64,
+        DataType::Utf8,
+    ]);
+    for blocks in [vec![block(5)], vec![block(u64::MAX)], vec![block(0), block(u64::MAX)]] {
+        assert_eq!(to_record_batch(&blocks).unwrap().schema(), empty.schema());
+    }
+    let empty = to_record_batch::<Payment>(&[]).unwrap();
+    let payments = to_record_batch(&[Payment {
+        amount: 1,
+        fee: 0,
+        label: s("rent"),
+    }])
+    .unwrap();
+    assert_eq!(payments.schema(), empty.schema());
+}
+
+#[test]
+fn invalid_cells() {
+    #[derive(Serialize)]
+    struct Price(String);
+    impl OutputCompact for Price {
+        fn output_compact(&self) -> String { self.0.clone() }
+    }
+    impl OutputFormat for Price {
+        fn output_headers() -> Vec<String> { vec![s("Price")] }
+        fn output_id_string(&self) -> String { self.0.clone() }
+        fn output_fields(&self) -> Vec<String> { vec![self.0.clone()] }
+        fn output_column_types() -> Vec<ColumnType> { vec![ColumnType::Int64] }
+    }
+
+    let batch = to_record_batch(&[Price(s("-3")), Price(s(""))]).unwrap();
+    let price = batch.column(0).as_primitive::<Int64Type>();
+    assert_eq!(price.value(0), -3);
+    assert!(price.is_null(1));
+    let err = to_record_batch(&[Price(s("free"))]).unwrap_err();
+    assert!(matches!(err, OutputError::Arrow(_)));
+    assert!(err.to_string().contains("value `free` of column `Price` is not i64"), "{}", err);
+}
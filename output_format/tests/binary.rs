@@ -0,0 +1,73 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Binary CBOR and MessagePack output.
+
+mod common;
+
+use std::collections::BTreeMap;
+use std::io;
+
+use output_format::{Formatting, OutputError, OutputFormat};
+
+use crate::common::{config, entry, Broken, Entry, FailingWriter};
+
+fn write(data: &impl OutputFormat, format: Formatting) -> Vec<u8> {
+    let mut buf = Vec::new();
+    data.output_write_with(format, &config(), &mut buf).unwrap();
+    buf
+}
+
+#[test]
+fn cbor_round_trip() {
+    let record = entry("alice", "rent");
+    let decoded: Entry =
+        ciborium::from_reader(write(&record, Formatting::Cbor).as_slice()).unwrap();
+    assert_eq!(decoded, record);
+
+    let map = BTreeMap::from([(s("k"), vec![entry("alice", "rent"), entry("bob", "")])]);
+    let decoded: BTreeMap<String, Vec<Entry>> =
+        ciborium::from_reader(write(&map, Formatting::Cbor).as_slice()).unwrap();
+    assert_eq!(decoded, map);
+}
+
+#[test]
+fn msgpack_round_trip() {
+    let record = entry("alice", "rent");
+    let decoded: Entry = rmp_serde::from_slice(&write(&record, Formatting::MsgPack)).unwrap();
+    assert_eq!(decoded, record);
+
+    let list = vec![entry("alice", "rent"), entry("bob", "")];
+    let decoded: Vec<Entry> = rmp_serde::from_slice(&write(&list, Formatting::MsgPack)).unwrap();
+    assert_eq!(decoded, list);
+}
+
+#[test]
+fn broken_pipe() {
+    let list = vec![entry("alice", "rent")];
+    for format in [Formatting::Cbor, Formatting::MsgPack] {
+        let mut pipe = FailingWriter(io::ErrorKind::BrokenPipe);
+        let err = list.output_write(format, &mut pipe).unwrap_err();
+        assert!(matches!(err, OutputError::BrokenPipe), "{}: {}", format, err);
+    }
+}
+
+#[test]
+fn serialization_error() {
+    let mut buf = Vec::new();
+    let err = vec![Broken].output_write(Formatting::Cbor, &mut buf).unwrap_err();
+    assert!(matches!(err, OutputError::Cbor(_)), "{}", err);
+    let err = vec![Broken].output_write(Formatting::MsgPack, &mut buf).unwrap_err();
+    assert!(matches!(err, OutputError::MsgPack(_)), "{}", err);
+}
+
+fn s(s: &str) -> String { s.to_owned() }
@@ -0,0 +1,91 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Records and helpers shared by the integration tests.
+
+#![allow(dead_code)]
+
+use std::io;
+
+use output_format::{ColorChoice, Formatting, OutputCompact, OutputConfig, OutputFormat};
+use serde::{Deserialize, Serialize, Serializer};
+
+#[derive(
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Debug,
+    Serialize,
+    Deserialize,
+    OutputFormat,
+    OutputCompact
+)]
+#[output(compact = "{name}")]
+pub struct Entry {
+    pub name: String,
+    pub memo: String,
+}
+
+pub fn entry(name: &str, memo: &str) -> Entry {
+    Entry {
+        name: name.to_owned(),
+        memo: memo.to_owned(),
+    }
+}
+
+/// Configuration with the colours disabled, independently of the
+/// environment the tests are run in.
+pub fn config() -> OutputConfig {
+    OutputConfig {
+        color: ColorChoice::Never,
+        ..OutputConfig::default()
+    }
+}
+
+/// Writes data into a buffer, returning the output as a string.
+pub fn render(data: &impl OutputFormat, format: Formatting, config: &OutputConfig) -> String {
+    let mut buf = Vec::new();
+    data.output_write_with(format, config, &mut buf).unwrap();
+    String::from_utf8(buf).unwrap()
+}
+
+/// Writer failing with the given kind of I/O error, like a pipe closed by
+/// the reading end does.
+pub struct FailingWriter(pub io::ErrorKind);
+
+impl io::Write for FailingWriter {
+    fn write(&mut self, _: &[u8]) -> io::Result<usize> { Err(self.0.into()) }
+
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
+
+/// Record which fails to serialize.
+pub struct Broken;
+
+impl Serialize for Broken {
+    fn serialize<S: Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom("broken record"))
+    }
+}
+
+impl OutputCompact for Broken {
+    fn output_compact(&self) -> String { String::from("broken") }
+}
+
+impl OutputFormat for Broken {
+    fn output_headers() -> Vec<String> { vec![String::from("name")] }
+    fn output_id_string(&self) -> String { String::from("broken") }
+    fn output_fields(&self) -> Vec<String> { vec![String::from("broken")] }
+}
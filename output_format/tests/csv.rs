@@ -0,0 +1,52 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! CSV output of maps, with the map keys prepended to each row.
+
+mod common;
+
+use std::collections::{BTreeMap, HashMap};
+
+use output_format::{Formatting, OutputConfig, OutputFormat};
+
+use crate::common::{config, entry, render};
+
+fn csv(data: &impl OutputFormat, crlf: bool) -> String {
+    let config = OutputConfig {
+        csv_crlf: crlf,
+        ..config()
+    };
+    render(data, Formatting::Csv, &config)
+}
+
+#[test]
+fn hash_map_rows() {
+    let map = HashMap::from([("key, one".to_owned(), entry("alice", "said \"hi\""))]);
+    assert_eq!(csv(&map, false), "ID,name,memo\n\"key, one\",alice,\"said \"\"hi\"\"\"\n");
+    assert_eq!(csv(&map, true), "ID,name,memo\r\n\"key, one\",alice,\"said \"\"hi\"\"\"\r\n");
+}
+
+#[test]
+fn btree_map_rows() {
+    let map = BTreeMap::from([
+        ("a\nb".to_owned(), vec![entry("alice", "x,y"), entry("bob", "")]),
+        ("c".to_owned(), vec![entry("carol", "multi\r\nline")]),
+    ]);
+    assert_eq!(
+        csv(&map, false),
+        "ID,name,memo\n\"a\nb\",alice,\"x,y\"\n\"a\nb\",bob,\nc,carol,\"multi\r\nline\"\n"
+    );
+    assert_eq!(
+        csv(&map, true),
+        "ID,name,memo\r\n\"a\nb\",alice,\"x,y\"\r\n\"a\nb\",bob,\r\nc,carol,\"multi\r\nline\"\r\n"
+    );
+}
@@ -0,0 +1,117 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Tests for `OutputFormat` and `OutputCompact` derive macros.
+
+use output_format::{ColumnType, OutputCompact, OutputFormat};
+use serde::Serialize;
+
+#[derive(Serialize, OutputFormat, OutputCompact)]
+#[output(compact = "{txid}:{vout}")]
+struct Utxo {
+    txid: String,
+    vout: u32,
+    #[output(header = "Amount")]
+    value: u64,
+    #[output(skip)]
+    #[allow(dead_code)]
+    secret: String,
+}
+
+#[derive(Serialize, OutputFormat, OutputCompact)]
+struct Account {
+    #[output(id)]
+    name: String,
+    balance: u64,
+}
+
+#[test]
+fn derive_columns() {
+    let utxo = Utxo {
+        txid: s("abcd"),
+        vout: 1,
+        value: 1000,
+        secret: s("hidden"),
+    };
+    assert_eq!(Utxo::output_headers(), vec!["txid", "vout", "Amount"]);
+    assert_eq!(utxo.output_fields(), vec!["abcd", "1", "1000"]);
+    assert_eq!(Utxo::output_column_types(), vec![
+        ColumnType::String,
+        ColumnType::UInt64,
+        ColumnType::UInt64
+    ]);
+}
+
+#[test]
+fn derive_compact_format() {
+    let utxo = Utxo {
+        txid: s("abcd"),
+        vout: 1,
+        value: 1000,
+        secret: s("hidden"),
+    };
+    assert_eq!(utxo.output_compact(), "abcd:1");
+    // Without an identifier field the compact representation is used as ID
+    assert_eq!(utxo.output_id_string(), "abcd:1");
+}
+
+#[test]
+fn derive_id() {
+    let account = Account {
+        name: s("alice"),
+        balance: 42,
+    };
+    assert_eq!(Account::output_headers(), vec!["name", "balance"]);
+    assert_eq!(account.output_fields(), vec!["alice", "42"]);
+    assert_eq!(account.output_id_string(), "alice");
+    assert_eq!(account.output_compact(), "alice");
+}
+
+#[derive(Serialize, OutputFormat, OutputCompact)]
+struct Payment {
+    #[output(id)]
+    label: Option<String>,
+    memo: Option<String>,
+    #[output(with = "join_tags")]
+    tags: Vec<String>,
+}
+
+fn join_tags(tags: &[String]) -> String { tags.join(";") }
+
+#[test]
+fn derive_optional_and_with() {
+    let payment = Payment {
+        label: Some(s("rent")),
+        memo: None,
+        tags: vec![s("home"), s("monthly")],
+    };
+    assert_eq!(Payment::output_headers(), vec!["label", "memo", "tags"]);
+    assert_eq!(payment.output_fields(), vec!["rent", "", "home;monthly"]);
+    assert_eq!(payment.output_id_string(), "rent");
+    // Formatted fields are strings whatever their type is
+    assert_eq!(Payment::output_column_types(), vec![ColumnType::String; 3]);
+    let payment = Payment {
+        label: None,
+        memo: Some(s("paid")),
+        tags: vec![],
+    };
+    assert_eq!(payment.output_fields(), vec!["", "paid", ""]);
+    assert_eq!(payment.output_compact(), "");
+}
+
+#[test]
+fn derive_errors() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
+
+fn s(s: &str) -> String { s.to_owned() }
@@ -0,0 +1,99 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Test vectors from RFC 8785 (JSON Canonicalization Scheme).
+
+use output_format::to_canonical_json;
+use serde_json::json;
+
+#[test]
+fn rfc8785_sample() {
+    // RFC 8785 section 3.2.2
+    let data = json!({
+        "numbers": [333333333.3333333, 1e30, 4.50, 2e-3, 0.000000000000000000000000001],
+        "string": "\u{20ac}$\u{000F}\u{000a}A'\u{0042}\u{0022}\u{005c}\\\"/",
+        "literals": [null, true, false]
+    });
+    assert_eq!(
+        to_canonical_json(&data).unwrap(),
+        r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#
+    );
+}
+
+#[test]
+fn rfc8785_sorting() {
+    // RFC 8785 section 3.2.3
+    let data = json!({
+        "\u{20ac}": "Euro Sign",
+        "\r": "Carriage Return",
+        "\u{fb33}": "Hebrew Letter Dalet With Dagesh",
+        "1": "One",
+        "\u{1f600}": "Emoji: Grinning Face",
+        "\u{0080}": "Control",
+        "\u{00f6}": "Latin Small Letter O With Diaeresis"
+    });
+    assert_eq!(
+        to_canonical_json(&data).unwrap(),
+        "{\"\\r\":\"Carriage Return\",\"1\":\"One\",\"\u{0080}\":\"Control\",\"\u{00f6}\":\"Latin \
+         Small Letter O With Diaeresis\",\"\u{20ac}\":\"Euro Sign\",\"\u{1f600}\":\"Emoji: \
+         Grinning Face\",\"\u{fb33}\":\"Hebrew Letter Dalet With Dagesh\"}"
+    );
+}
+
+#[test]
+fn rfc8785_numbers() {
+    // RFC 8785 appendix B
+    let vectors: [(u64, &str); 20] = [
+        (0x0000000000000000, "0"),
+        (0x8000000000000000, "0"),
+        (0x0000000000000001, "5e-324"),
+        (0x8000000000000001, "-5e-324"),
+        (0x7fefffffffffffff, "1.7976931348623157e+308"),
+        (0xffefffffffffffff, "-1.7976931348623157e+308"),
+        (0x4340000000000000, "9007199254740992"),
+        (0xc340000000000000, "-9007199254740992"),
+        (0x4430000000000000, "295147905179352830000"),
+        (0x44b52d02c7e14af5, "9.999999999999997e+22"),
+        (0x44b52d02c7e14af6, "1e+23"),
+        (0x44b52d02c7e14af7, "1.0000000000000001e+23"),
+        (0x444b1ae4d6e2ef4e, "999999999999999700000"),
+        (0x444b1ae4d6e2ef4f, "999999999999999900000"),
+        (0x444b1ae4d6e2ef50, "1e+21"),
+        (0x3eb0c6f7a0b5ed8c, "9.999999999999997e-7"),
+        (0x3eb0c6f7a0b5ed8d, "0.000001"),
+        (0x41b3de4355555553, "333333333.3333332"),
+        (0x41b3de4355555554, "333333333.33333325"),
+        (0x41b3de4355555555, "333333333.3333333"),
+    ];
+    for (bits, expected) in vectors {
+        let value = f64::from_bits(bits);
+        assert_eq!(to_canonical_json(&value).unwrap(), expected, "{:016x}", bits);
+    }
+}
+
+#[test]
+fn integers_beyond_double_precision() {
+    assert_eq!(to_canonical_json(&u64::MAX).unwrap(), "18446744073709552000");
+    assert_eq!(to_canonical_json(&2_100_000_000_000_000_u64).unwrap(), "2100000000000000");
+}
+
+#[test]
+fn non_finite() {
+    for value in [f64::INFINITY, f64::NEG_INFINITY, f64::NAN] {
+        let err = to_canonical_json(&vec![value]).unwrap_err();
+        assert!(err.to_string().contains("can't be represented in canonical JSON"));
+    }
+    let nested = json!({ "a": [1.5, { "b": null }] });
+    assert_eq!(to_canonical_json(&nested).unwrap(), r#"{"a":[1.5,{"b":null}]}"#);
+    let map = std::collections::BTreeMap::from([("x", Some(f32::NAN))]);
+    assert!(to_canonical_json(&map).is_err());
+}
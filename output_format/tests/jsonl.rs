@@ -0,0 +1,79 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! JSON Lines output of maps, with the map key put before the record fields.
+
+use std::collections::{BTreeMap, HashMap};
+
+use output_format::{Formatting, OutputCompact, OutputConfig, OutputError, OutputFormat};
+use serde::Serialize;
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, OutputFormat, OutputCompact)]
+#[output(compact = "{zulu}")]
+struct Entry {
+    zulu: String,
+    id: u8,
+    alpha: u8,
+}
+
+fn jsonl(data: &impl OutputFormat, config: &OutputConfig) -> String {
+    let mut buf = Vec::new();
+    data.output_write_with(Formatting::JsonLines, config, &mut buf).unwrap();
+    String::from_utf8(buf).unwrap()
+}
+
+#[test]
+fn keyed_fields() {
+    let entry = Entry {
+        zulu: "z".to_owned(),
+        id: 7,
+        alpha: 1,
+    };
+    let map = BTreeMap::from([("key".to_owned(), vec![entry])]);
+    assert_eq!(
+        jsonl(&map, &OutputConfig::default()),
+        "{\"id\":\"key\",\"value\":{\"zulu\":\"z\",\"id\":7,\"alpha\":1}}\n"
+    );
+    let config = OutputConfig {
+        jsonl_key_field: "key".to_owned(),
+        ..OutputConfig::default()
+    };
+    assert_eq!(jsonl(&map, &config), "{\"key\":\"key\",\"zulu\":\"z\",\"id\":7,\"alpha\":1}\n");
+}
+
+/// Record serialized as a plain string rather than an object.
+#[derive(Clone, Serialize)]
+struct Label(String);
+
+impl OutputCompact for Label {
+    fn output_compact(&self) -> String { self.0.clone() }
+}
+
+impl OutputFormat for Label {
+    fn output_headers() -> Vec<String> { vec!["label".to_owned()] }
+    fn output_id_string(&self) -> String { self.0.clone() }
+    fn output_fields(&self) -> Vec<String> { vec![self.0.clone()] }
+}
+
+#[test]
+fn keyed_non_objects() {
+    let map = HashMap::from([("key".to_owned(), Label("rent".to_owned()))]);
+    assert_eq!(jsonl(&map, &OutputConfig::default()), "{\"id\":\"key\",\"value\":\"rent\"}\n");
+    let config = OutputConfig {
+        jsonl_key_field: "value".to_owned(),
+        ..OutputConfig::default()
+    };
+    let mut buf = Vec::new();
+    let err = map.output_write_with(Formatting::JsonLines, &config, &mut buf).unwrap_err();
+    assert!(matches!(err, OutputError::KeyCollision(ref name) if name == "value"));
+    assert!(buf.is_empty());
+}
@@ -0,0 +1,139 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Writing data into arbitrary writers and reporting output errors.
+
+mod common;
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io;
+
+use output_format::{Formatting, OutputConfig, OutputError, OutputFormat};
+
+use crate::common::{config, entry, render, Broken, Entry, FailingWriter};
+
+#[test]
+fn write_into_buffer() {
+    let record = entry("alice", "rent");
+    let mut buf = Vec::new();
+    record.output_write(Formatting::Compact, &mut buf).unwrap();
+    record.output_write(Formatting::Json, &mut buf).unwrap();
+    assert_eq!(String::from_utf8(buf).unwrap(), "alice\n{\"name\":\"alice\",\"memo\":\"rent\"}\n");
+}
+
+#[test]
+fn write_collections() {
+    let list = vec![entry("alice", "rent"), entry("bob", "food")];
+    let expected = "name\tmemo\nalice\trent\nbob\tfood\n";
+    assert_eq!(render(&list, Formatting::Tab, &config()), expected);
+    let set = list.iter().cloned().collect::<BTreeSet<_>>();
+    assert_eq!(render(&set, Formatting::Tab, &config()), expected);
+    let set = HashSet::from([entry("alice", "rent")]);
+    assert_eq!(render(&set, Formatting::Tab, &config()), "name\tmemo\nalice\trent\n");
+    let map = HashMap::from([("k", entry("alice", "rent"))]);
+    assert_eq!(render(&map, Formatting::Id, &config()), "k\n");
+    let map = BTreeMap::from([("k", list)]);
+    assert_eq!(render(&map, Formatting::Compact, &config()), "alice#k\nbob#k\n");
+}
+
+#[test]
+fn serialization_error() {
+    let mut buf = Vec::new();
+    let err = Broken.output_write(Formatting::Json, &mut buf).unwrap_err();
+    assert!(matches!(err, OutputError::Json(_)));
+    assert_eq!(err.to_string(), "unable to serialize data as JSON: broken record");
+    let err = vec![Broken].output_write(Formatting::Yaml, &mut buf).unwrap_err();
+    assert!(matches!(err, OutputError::Yaml(_)));
+    assert!(buf.is_empty());
+    // Formats not involving serialization are not affected
+    Broken.output_write(Formatting::Tab, &mut buf).unwrap();
+    assert_eq!(buf, b"broken\n");
+}
+
+#[test]
+fn broken_pipe() {
+    let list = vec![entry("alice", "rent"), entry("bob", "food")];
+    let map = BTreeMap::from([("k", list.clone())]);
+    for format in
+        [Formatting::Id, Formatting::Tab, Formatting::Csv, Formatting::Table, Formatting::Json]
+    {
+        let mut pipe = FailingWriter(io::ErrorKind::BrokenPipe);
+        let err = list.output_write(format, &mut pipe).unwrap_err();
+        assert!(matches!(err, OutputError::BrokenPipe), "{} list: {}", format, err);
+        let err = map.output_write(format, &mut pipe).unwrap_err();
+        assert!(matches!(err, OutputError::BrokenPipe), "{} map: {}", format, err);
+    }
+    let mut disk = FailingWriter(io::ErrorKind::StorageFull);
+    let err = list.output_write(Formatting::Tab, &mut disk).unwrap_err();
+    assert!(matches!(err, OutputError::Io(ref err) if err.kind() == io::ErrorKind::StorageFull));
+}
+
+#[test]
+fn empty_collections() {
+    let list = Vec::<Entry>::new();
+    assert_eq!(render(&list, Formatting::Json, &config()), "[]\n");
+    assert_eq!(render(&list, Formatting::Yaml, &config()), "---\n[]\n\n");
+    assert_eq!(render(&BTreeSet::<Entry>::new(), Formatting::Json, &config()), "[]\n");
+    assert_eq!(render(&HashMap::<String, Entry>::new(), Formatting::Json, &config()), "{}\n");
+    for format in [Formatting::Tab, Formatting::Csv] {
+        let mut buf = Vec::new();
+        let err = list.output_write(format, &mut buf).unwrap_err();
+        assert!(matches!(err, OutputError::EmptyCollection));
+        let map = BTreeMap::<String, Vec<Entry>>::from([(String::from("k"), vec![])]);
+        let err = map.output_write(format, &mut buf).unwrap_err();
+        assert!(matches!(err, OutputError::EmptyCollection));
+        assert!(buf.is_empty());
+    }
+}
+
+#[test]
+fn single_sequence() {
+    let list = vec![entry("alice", "rent"), entry("bob", "food")];
+    let json = render(&list, Formatting::Json, &config());
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+        serde_json::json!([
+            { "name": "alice", "memo": "rent" },
+            { "name": "bob", "memo": "food" },
+        ])
+    );
+    assert_eq!(
+        render(&list, Formatting::Yaml, &config()),
+        "---\n- name: alice\n  memo: rent\n- name: bob\n  memo: food\n\n"
+    );
+}
+
+#[test]
+fn json_indent() {
+    let map = BTreeMap::from([("k", vec![entry("alice", "rent")])]);
+    assert_eq!(
+        render(&map, Formatting::JsonPretty, &config()),
+        "{\n  \"k\": [\n    {\n      \"name\": \"alice\",\n      \"memo\": \"rent\"\n    }\n  \
+         ]\n}\n"
+    );
+    let config = OutputConfig {
+        json_indent: 4,
+        ..config()
+    };
+    assert_eq!(
+        render(&entry("alice", "rent"), Formatting::JsonPretty, &config),
+        "{\n    \"name\": \"alice\",\n    \"memo\": \"rent\"\n}\n"
+    );
+    let config = OutputConfig {
+        json_indent: 0,
+        ..config
+    };
+    assert_eq!(
+        render(&vec![entry("alice", "rent")], Formatting::JsonPretty, &config),
+        "[\n{\n\"name\": \"alice\",\n\"memo\": \"rent\"\n}\n]\n"
+    );
+}
@@ -0,0 +1,77 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! SQL dump output.
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use output_format::{Formatting, OutputCompact, OutputConfig, OutputFormat};
+use serde::Serialize;
+
+use crate::common::{config, entry, render};
+
+#[derive(Serialize, OutputFormat, OutputCompact)]
+struct Quote {
+    #[output(id)]
+    author: String,
+    #[output(header = r#"the "text""#)]
+    text: String,
+}
+
+#[test]
+fn quoting() {
+    let quotes = vec![
+        Quote {
+            author: "O'Brien".to_owned(),
+            text: r#"say "hi""#.to_owned(),
+        },
+        Quote {
+            author: "Anon".to_owned(),
+            text: "it's 'quoted'".to_owned(),
+        },
+    ];
+    let config = OutputConfig {
+        sql_table: r#"quotes "2024""#.to_owned(),
+        ..config()
+    };
+    assert_eq!(
+        render(&quotes, Formatting::Sql, &config),
+        r#"BEGIN TRANSACTION;
+CREATE TABLE "quotes ""2024""" (
+  "author" TEXT,
+  "the ""text""" TEXT
+);
+INSERT INTO "quotes ""2024""" ("author", "the ""text""") VALUES ('O''Brien', 'say "hi"');
+INSERT INTO "quotes ""2024""" ("author", "the ""text""") VALUES ('Anon', 'it''s ''quoted''');
+COMMIT;
+"#
+    );
+}
+
+#[test]
+fn map_rows() {
+    let map = BTreeMap::from([("k'1".to_owned(), vec![entry("alice", "rent")])]);
+    assert_eq!(
+        render(&map, Formatting::Sql, &config()),
+        r#"BEGIN TRANSACTION;
+CREATE TABLE "items" (
+  "ID" TEXT,
+  "name" TEXT,
+  "memo" TEXT
+);
+INSERT INTO "items" ("ID", "name", "memo") VALUES ('k''1', 'alice', 'rent');
+COMMIT;
+"#
+    );
+}
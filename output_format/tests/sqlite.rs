@@ -0,0 +1,121 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Writing data directly into SQLite database files.
+
+#![cfg(feature = "sqlite")]
+
+use std::collections::BTreeMap;
+
+use output_format::{
+    write_sqlite, Formatting, OutputCompact, OutputConfig, OutputError, OutputFormat,
+};
+use serde::Serialize;
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, OutputFormat, OutputCompact)]
+#[output(compact = "{txid}:{vout}")]
+struct Utxo {
+    txid: String,
+    vout: u32,
+    #[output(header = "Memo")]
+    memo: String,
+}
+
+fn utxo(txid: &str, vout: u32, memo: &str) -> Utxo {
+    Utxo {
+        txid: txid.to_owned(),
+        vout,
+        memo: memo.to_owned(),
+    }
+}
+
+fn read_rows(path: &std::path::Path, query: &str) -> Vec<Vec<String>> {
+    let connection = rusqlite::Connection::open(path).unwrap();
+    let mut statement = connection.prepare(query).unwrap();
+    let columns = statement.column_count();
+    statement
+        .query_map([], |row| (0..columns).map(|no| row.get::<_, String>(no)).collect())
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+#[test]
+fn write_list() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("utxos.sqlite");
+    let utxos = vec![utxo("ab", 0, "it's \"quoted\"; DROP TABLE items;"), utxo("cd", 1, "")];
+    write_sqlite(&utxos, &path, &OutputConfig::default()).unwrap();
+    assert_eq!(read_rows(&path, "SELECT txid, vout, \"Memo\" FROM items ORDER BY rowid"), vec![
+        vec!["ab", "0", "it's \"quoted\"; DROP TABLE items;"],
+        vec!["cd", "1", ""],
+    ]);
+}
+
+#[test]
+fn write_map() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("utxos.sqlite");
+    let map = BTreeMap::from([
+        ("alice".to_owned(), vec![utxo("ab", 0, "x"), utxo("cd", 1, "y")]),
+        ("bob".to_owned(), vec![utxo("ef", 2, "z")]),
+    ]);
+    let config = OutputConfig {
+        sql_table: "wallet utxos".to_owned(),
+        ..OutputConfig::default()
+    };
+    write_sqlite(&map, &path, &config).unwrap();
+    assert_eq!(read_rows(&path, "SELECT * FROM \"wallet utxos\" ORDER BY rowid"), vec![
+        vec!["alice", "ab", "0", "x"],
+        vec!["alice", "cd", "1", "y"],
+        vec!["bob", "ef", "2", "z"],
+    ]);
+}
+
+#[test]
+fn sql_dump() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("utxos.sqlite");
+    let utxos = vec![utxo("ab", 0, "it's \"quoted\"; DROP TABLE items;"), utxo("cd", 1, "")];
+    let config = OutputConfig {
+        sql_table: "it's \"utxos\"".to_owned(),
+        ..OutputConfig::default()
+    };
+    let mut dump = Vec::new();
+    utxos.output_write_with(Formatting::Sql, &config, &mut dump).unwrap();
+    let connection = rusqlite::Connection::open(&path).unwrap();
+    connection.execute_batch(&String::from_utf8(dump).unwrap()).unwrap();
+    assert_eq!(read_rows(&path, "SELECT * FROM \"it's \"\"utxos\"\"\" ORDER BY rowid"), vec![
+        vec!["ab", "0", "it's \"quoted\"; DROP TABLE items;"],
+        vec!["cd", "1", ""],
+    ]);
+}
+
+#[test]
+fn existing_table() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("utxos.sqlite");
+    let utxos = vec![utxo("ab", 0, "secret memo")];
+    write_sqlite(&utxos, &path, &OutputConfig::default()).unwrap();
+    let err = write_sqlite(&utxos, &path, &OutputConfig::default()).unwrap_err();
+    assert!(matches!(err, OutputError::Sqlite(_)));
+    assert!(!err.to_string().contains("secret memo"));
+    assert_eq!(read_rows(&path, "SELECT CAST(count(*) AS TEXT) FROM items"), vec![vec!["1"]]);
+}
+
+#[test]
+fn empty() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("utxos.sqlite");
+    let err = write_sqlite(&Vec::<Utxo>::new(), &path, &OutputConfig::default()).unwrap_err();
+    assert!(matches!(err, OutputError::EmptyCollection));
+}
@@ -0,0 +1,62 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! TOML output of records and collections.
+
+mod common;
+
+use std::collections::HashMap;
+
+use output_format::{Formatting, OutputConfig, OutputError, OutputFormat};
+
+use crate::common::{config, entry, render, Entry};
+
+#[test]
+fn record() {
+    assert_eq!(
+        render(&entry("alice", "rent"), Formatting::Toml, &config()),
+        "name = \"alice\"\nmemo = \"rent\"\n"
+    );
+}
+
+#[test]
+fn list_key() {
+    let list = vec![entry("alice", "rent"), entry("bob", "food")];
+    assert_eq!(
+        render(&list, Formatting::Toml, &config()),
+        "[[items]]\nname = \"alice\"\nmemo = \"rent\"\n\n[[items]]\nname = \"bob\"\nmemo = \
+         \"food\"\n"
+    );
+    let config = OutputConfig {
+        toml_list_key: "payment".to_owned(),
+        ..config()
+    };
+    assert_eq!(
+        render(&list[..1].to_vec(), Formatting::Toml, &config),
+        "[[payment]]\nname = \"alice\"\nmemo = \"rent\"\n"
+    );
+    assert_eq!(render(&Vec::<Entry>::new(), Formatting::Toml, &config), "payment = []\n");
+}
+
+#[test]
+fn key_not_string() {
+    let map = HashMap::from([(1u32, entry("alice", "rent"))]);
+    let mut buf = Vec::new();
+    let err = map.output_write(Formatting::Toml, &mut buf).unwrap_err();
+    assert!(matches!(err, OutputError::Toml(_)), "{}", err);
+    assert_eq!(err.to_string(), "data can't be represented as TOML: map key was not a string");
+    let map = HashMap::from([("k".to_owned(), entry("alice", "rent"))]);
+    assert_eq!(
+        render(&map, Formatting::Toml, &config()),
+        "[k]\nname = \"alice\"\nmemo = \"rent\"\n"
+    );
+}
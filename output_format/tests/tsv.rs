@@ -0,0 +1,43 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Tab-separated output of maps, with the escaped map keys prepended to each
+//! row.
+
+mod common;
+
+use std::collections::{BTreeMap, HashMap};
+
+use output_format::Formatting;
+
+use crate::common::{config, entry, render};
+
+#[test]
+fn hash_map_rows() {
+    let map = HashMap::from([("key\tone".to_owned(), entry("alice", "C:\\temp"))]);
+    assert_eq!(
+        render(&map, Formatting::Tab, &config()),
+        "ID\tname\tmemo\nkey\\tone\talice\tC:\\\\temp\n"
+    );
+}
+
+#[test]
+fn btree_map_rows() {
+    let map = BTreeMap::from([
+        ("a\nb".to_owned(), vec![entry("alice", "x\ty"), entry("bob", "")]),
+        ("c\\d".to_owned(), vec![entry("carol", "multi\r\nline")]),
+    ]);
+    assert_eq!(
+        render(&map, Formatting::Tab, &config()),
+        "ID\tname\tmemo\na\\nb\talice\tx\\ty\na\\nb\tbob\t\nc\\\\d\tcarol\tmulti\\r\\nline\n"
+    );
+}
@@ -0,0 +1,9 @@
+use output_format::OutputCompact;
+
+#[derive(OutputCompact)]
+struct Account {
+    name: String,
+    balance: u64,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use output_format::{OutputCompact, OutputFormat};
+use serde::Serialize;
+
+#[derive(Serialize, OutputFormat, OutputCompact)]
+struct Account {
+    #[output(id)]
+    name: String,
+    #[output(id)]
+    alias: String,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use output_format::{OutputCompact, OutputFormat};
+use serde::Serialize;
+
+#[derive(Serialize, OutputFormat, OutputCompact)]
+struct Account(String, u64);
+
+fn main() {}
@@ -0,0 +1,52 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Excel workbook output.
+
+mod common;
+
+use std::collections::BTreeMap;
+use std::io::Cursor;
+
+use calamine::{Data, Reader, Xlsx};
+use output_format::{Formatting, OutputFormat};
+
+use crate::common::{config, entry};
+
+fn open(data: &impl OutputFormat) -> Xlsx<Cursor<Vec<u8>>> {
+    let mut buf = Vec::new();
+    data.output_write_with(Formatting::Xlsx, &config(), &mut buf).unwrap();
+    Xlsx::new(Cursor::new(buf)).unwrap()
+}
+
+#[test]
+fn sheet_per_key() {
+    let map = BTreeMap::from([
+        ("alice".to_owned(), vec![entry("alice", "rent"), entry("alice", "food")]),
+        ("bob".to_owned(), vec![entry("bob", "007")]),
+        ("Bob".to_owned(), vec![]),
+    ]);
+    let mut workbook = open(&map);
+    assert_eq!(workbook.sheet_names(), ["Bob", "alice", "bob (2)"]);
+
+    let range = workbook.worksheet_range("alice").unwrap();
+    let rows = range
+        .rows()
+        .map(|row| row.iter().map(Data::to_string).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    assert_eq!(rows, [["name", "memo"], ["alice", "rent"], ["alice", "food"]]);
+    // Cells are kept as text, without stripping leading zeros
+    let range = workbook.worksheet_range("bob (2)").unwrap();
+    assert_eq!(range.get_value((1, 1)), Some(&Data::String("007".to_owned())));
+    let range = workbook.worksheet_range("Bob").unwrap();
+    assert_eq!(range.rows().count(), 1);
+}
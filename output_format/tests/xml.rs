@@ -0,0 +1,105 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! XML output of records and collections.
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use output_format::{Formatting, OutputCompact, OutputConfig, OutputFormat, XmlKey};
+use serde::Serialize;
+
+use crate::common::{config, entry, render, Entry};
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, OutputFormat, OutputCompact)]
+struct Invoice {
+    #[output(id)]
+    id: u32,
+    total: String,
+}
+
+#[test]
+fn key_collision() {
+    let map = BTreeMap::from([("k", vec![Invoice {
+        id: 7,
+        total: "10".to_owned(),
+    }])]);
+    let config = OutputConfig {
+        xml_key: XmlKey::Element,
+        ..config()
+    };
+    assert_eq!(
+        render(&map, Formatting::Xml, &config),
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<items>\n  <item>\n    <id>k</id>\n    \
+         <value>\n      <id>7</id>\n      <total>10</total>\n    </value>\n  </item>\n</items>\n"
+    );
+}
+
+#[test]
+fn record() {
+    assert_eq!(
+        render(&entry("a&b", "<tag>"), Formatting::Xml, &config()),
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<item>\n  <name>a&amp;b</name>\n  \
+         <memo>&lt;tag&gt;</memo>\n</item>\n"
+    );
+}
+
+#[test]
+fn list() {
+    let list = vec![entry("alice", "it's"), entry("bob", "")];
+    let config = OutputConfig {
+        xml_root: "2 payments".to_owned(),
+        xml_item: "payment".to_owned(),
+        ..config()
+    };
+    assert_eq!(
+        render(&list, Formatting::Xml, &config),
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<_2_payments>\n  <payment>\n    \
+         <name>alice</name>\n    <memo>it&apos;s</memo>\n  </payment>\n  <payment>\n    \
+         <name>bob</name>\n    <memo></memo>\n  </payment>\n</_2_payments>\n"
+    );
+    assert_eq!(
+        render(&Vec::<Entry>::new(), Formatting::Xml, &config),
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<_2_payments/>\n"
+    );
+}
+
+#[test]
+fn key_attribute() {
+    let map = BTreeMap::from([("\"k\" & v", vec![entry("alice", "rent")])]);
+    let config = OutputConfig {
+        xml_key_name: "wallet id".to_owned(),
+        ..config()
+    };
+    assert_eq!(
+        render(&map, Formatting::Xml, &config),
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<items>\n  <item wallet_id=\"&quot;k&quot; \
+         &amp; v\">\n    <name>alice</name>\n    <memo>rent</memo>\n  </item>\n</items>\n"
+    );
+}
+
+#[test]
+fn key_element() {
+    let map = BTreeMap::from([("<k>", vec![entry("alice", "rent")])]);
+    let config = OutputConfig {
+        xml_key: XmlKey::Element,
+        xml_key_name: "wallet".to_owned(),
+        ..config()
+    };
+    assert_eq!(
+        render(&map, Formatting::Xml, &config),
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<items>\n  <item>\n    \
+         <wallet>&lt;k&gt;</wallet>\n    <name>alice</name>\n    <memo>rent</memo>\n  \
+         </item>\n</items>\n"
+    );
+}
@@ -0,0 +1,286 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Derive macros for `OutputFormat` and `OutputCompact` traits from the
+//! `output_format` crate.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{
+    Data, DeriveInput, Fields, GenericArgument, Ident, LitStr, Path, PathArguments, PathSegment,
+    Type,
+};
+
+/// Derives `OutputFormat` for a struct with named fields.
+///
+/// Each field becomes a column, with the field name used as a header. Field
+/// values are converted into strings using their `Display` implementation.
+/// Supported field attributes:
+/// - `#[output(id)]`: the field is used as the record identifier;
+/// - `#[output(header = "Amount")]`: overrides the column header;
+/// - `#[output(skip)]`: excludes the field from the columns;
+/// - `#[output(with = "path::to::function")]`: converts the field into a string with a function
+///   taking it by reference, for fields not implementing `Display`.
+///
+/// Fields of `Option<T>` type are output as empty cells when they are `None`.
+///
+/// Column types reported by `output_column_types` are derived from the field
+/// types: `bool` gives boolean columns, signed and unsigned integers up to 64
+/// bits give `Int64` and `UInt64` columns, `f32` and `f64` give `Float64`
+/// columns, and `Option<T>` takes the type of `T`. Fields of any other type,
+/// including type aliases, and fields using `with` are string columns.
+///
+/// If no field is marked as identifier, the compact representation of the
+/// record is used instead.
+///
+/// The generated code refers to the `output_format` crate by its absolute
+/// path. If the crate is accessible only through a re-export, the path can be
+/// overridden with the container attribute
+/// `#[output(crate = "util::output_format")]`.
+#[proc_macro_derive(OutputFormat, attributes(output))]
+pub fn derive_output_format(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    output_format_inner(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Derives `OutputCompact` for a struct with named fields.
+///
+/// The compact representation is defined by the container attribute
+/// `#[output(compact = "{txid}:{vout}")]`, where names in braces refer to the
+/// struct fields. If the attribute is absent, the field marked with
+/// `#[output(id)]` is used. The path to the `output_format` crate can be
+/// overridden with `#[output(crate = "...")]` like for the `OutputFormat`
+/// derive.
+#[proc_macro_derive(OutputCompact, attributes(output))]
+pub fn derive_output_compact(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    output_compact_inner(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+struct ContainerAttrs {
+    compact: Option<LitStr>,
+    krate: Path,
+}
+
+impl ContainerAttrs {
+    fn parse(input: &DeriveInput) -> syn::Result<Self> {
+        let mut attrs = ContainerAttrs {
+            compact: None,
+            krate: syn::parse_quote! { ::output_format },
+        };
+        for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("output")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("compact") {
+                    attrs.compact = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("crate") {
+                    attrs.krate = meta.value()?.parse::<LitStr>()?.parse()?;
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown `output` container attribute"))
+                }
+            })?;
+        }
+        Ok(attrs)
+    }
+}
+
+struct FieldInfo {
+    ident: Ident,
+    header: String,
+    id: bool,
+    skip: bool,
+    optional: bool,
+    column: &'static str,
+    with: Option<Path>,
+}
+
+impl FieldInfo {
+    fn parse(field: &syn::Field) -> syn::Result<Self> {
+        let ident = field.ident.clone().expect("named fields always have identifiers");
+        let mut info = FieldInfo {
+            header: ident.to_string(),
+            ident,
+            id: false,
+            skip: false,
+            optional: option_inner(&field.ty).is_some(),
+            column: column_type(option_inner(&field.ty).unwrap_or(&field.ty)),
+            with: None,
+        };
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("output")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("id") {
+                    info.id = true;
+                } else if meta.path.is_ident("skip") {
+                    info.skip = true;
+                } else if meta.path.is_ident("header") {
+                    info.header = meta.value()?.parse::<LitStr>()?.value();
+                } else if meta.path.is_ident("with") {
+                    info.with = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                    info.column = "String";
+                } else {
+                    return Err(meta.error("unknown `output` field attribute"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(info)
+    }
+
+    /// Generates expression converting the field value into a string.
+    fn render(&self) -> TokenStream2 {
+        let ident = &self.ident;
+        match &self.with {
+            Some(with) => quote! { #with(&self.#ident) },
+            None if self.optional => quote! {
+                match &self.#ident {
+                    ::std::option::Option::Some(value) => ::std::string::ToString::to_string(value),
+                    ::std::option::Option::None => ::std::string::String::new(),
+                }
+            },
+            None => quote! { ::std::string::ToString::to_string(&self.#ident) },
+        }
+    }
+}
+
+/// Detects `Option<T>` field types by the name of the type, such that the
+/// missing values can be output as empty cells. Returns the type of the
+/// option value.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let segment = last_segment(ty).filter(|segment| segment.ident == "Option")?;
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Detects the column type by the name of a primitive field type, returning
+/// the name of the `ColumnType` variant.
+fn column_type(ty: &Type) -> &'static str {
+    let Some(segment) = last_segment(ty).filter(|segment| segment.arguments.is_empty()) else {
+        return "String";
+    };
+    match segment.ident.to_string().as_str() {
+        "bool" => "Boolean",
+        "i8" | "i16" | "i32" | "i64" | "isize" => "Int64",
+        "u8" | "u16" | "u32" | "u64" | "usize" => "UInt64",
+        "f32" | "f64" => "Float64",
+        _ => "String",
+    }
+}
+
+fn last_segment(ty: &Type) -> Option<&PathSegment> {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last(),
+        _ => None,
+    }
+}
+
+fn parse_fields(input: &DeriveInput) -> syn::Result<Vec<FieldInfo>> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new(
+                    data.fields.span(),
+                    "output derive macros support only structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                input.span(),
+                "output derive macros support only structs with named fields",
+            ))
+        }
+    };
+    let fields = fields.iter().map(FieldInfo::parse).collect::<syn::Result<Vec<_>>>()?;
+    let mut ids = fields.iter().filter(|field| field.id);
+    if let (Some(_), Some(second)) = (ids.next(), ids.next()) {
+        return Err(syn::Error::new(
+            second.ident.span(),
+            "only a single field can be marked with `#[output(id)]`",
+        ));
+    }
+    Ok(fields)
+}
+
+fn output_format_inner(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let ContainerAttrs { krate, .. } = ContainerAttrs::parse(&input)?;
+    let fields = parse_fields(&input)?;
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let columns = fields.iter().filter(|field| !field.skip);
+    let headers = columns.clone().map(|field| &field.header);
+    let types = columns.clone().map(|field| Ident::new(field.column, field.ident.span()));
+    let values = columns.map(FieldInfo::render);
+    let id = match fields.iter().find(|field| field.id) {
+        Some(field) => field.render(),
+        None => quote! { #krate::OutputCompact::output_compact(self) },
+    };
+
+    Ok(quote! {
+        impl #impl_generics #krate::OutputFormat for #ident #ty_generics #where_clause {
+            fn output_headers() -> ::std::vec::Vec<::std::string::String> {
+                ::std::vec![#( ::std::string::ToString::to_string(#headers) ),*]
+            }
+
+            fn output_id_string(&self) -> ::std::string::String { #id }
+
+            fn output_fields(&self) -> ::std::vec::Vec<::std::string::String> {
+                ::std::vec![#( #values ),*]
+            }
+
+            fn output_column_types() -> ::std::vec::Vec<#krate::ColumnType> {
+                ::std::vec![#( #krate::ColumnType::#types ),*]
+            }
+        }
+    })
+}
+
+fn output_compact_inner(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let ContainerAttrs { compact, krate } = ContainerAttrs::parse(&input)?;
+    let fields = parse_fields(&input)?;
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let compact = match (compact, fields.iter().find(|field| field.id)) {
+        (Some(format), _) => {
+            let bindings = fields.iter().map(|field| &field.ident);
+            quote! {
+                #[allow(unused_variables)]
+                let Self { #( #bindings ),* } = self;
+                ::std::format!(#format)
+            }
+        }
+        (None, Some(field)) => field.render(),
+        (None, None) => {
+            return Err(syn::Error::new(
+                input.span(),
+                "`OutputCompact` derive requires either `#[output(compact = \"...\")]` container \
+                 attribute or a field marked with `#[output(id)]`",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics #krate::OutputCompact for #ident #ty_generics #where_clause {
+            fn output_compact(&self) -> ::std::string::String { #compact }
+        }
+    })
+}
@@ -0,0 +1,35 @@
+// Small rust utility crates used across codebase by Pandora projects.
+//
+// Written in 2021-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// To the extent possible under law, the author(s) have dedicated all copyright and related and
+// neighboring rights to this software to the public domain worldwide. This software is distributed
+// without any warranty.
+//
+// You should have received a copy of the MIT License along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Derive macros used through the `util` umbrella crate re-export.
+
+use serde::Serialize;
+use util::output_format::{OutputCompact, OutputFormat};
+
+#[derive(Serialize, OutputFormat, OutputCompact)]
+#[output(crate = "util::output_format")]
+struct Account {
+    #[output(id)]
+    name: String,
+    balance: u64,
+}
+
+#[test]
+fn derive_with_crate_override() {
+    let account = Account {
+        name: "alice".to_owned(),
+        balance: 42,
+    };
+    assert_eq!(Account::output_headers(), vec!["name", "balance"]);
+    assert_eq!(account.output_fields(), vec!["alice", "42"]);
+    assert_eq!(account.output_compact(), "alice");
+}